@@ -0,0 +1,266 @@
+use bytesize::ByteSize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// First line of a Git LFS pointer file
+const LFS_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// Pointer files are small; anything larger is real content
+const MAX_LFS_POINTER: usize = 1024;
+
+/// A Git LFS pointer: the object it stands for, which isn't checked out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsPointer {
+    /// SHA-256 of the object, in hex
+    pub oid: String,
+    /// Size of the object in bytes
+    pub size: u64,
+}
+
+/// Parse a Git LFS pointer file. Returns `None` for any other content.
+pub fn parse_lfs_pointer(content: &[u8]) -> Option<LfsPointer> {
+    if content.len() > MAX_LFS_POINTER {
+        return None;
+    }
+    let text = std::str::from_utf8(content).ok()?;
+    let mut lines = text.lines();
+    if lines.next()?.trim_end() != LFS_VERSION {
+        return None;
+    }
+    let (mut oid, mut size) = (None, None);
+    for line in lines {
+        let (key, value) = line.split_once(' ')?;
+        match key {
+            "oid" => oid = value.strip_prefix("sha256:").map(str::to_string),
+            "size" => size = value.trim().parse().ok(),
+            _ => {}
+        }
+    }
+    Some(LfsPointer {
+        oid: oid?,
+        size: size?,
+    })
+}
+
+/// One-line description of a binary file: its MIME type, size, image
+/// dimensions if it is an image, and SHA-256
+pub fn describe_binary(path: &str, content: &[u8]) -> String {
+    let mut details = vec![
+        detect_mime(path, content).to_string(),
+        ByteSize::b(content.len() as u64).to_string(),
+    ];
+    if let Some((width, height)) = image_dimensions(content) {
+        details.push(format!("{}x{}", width, height));
+    }
+    details.push(format!("sha256 {:x}", Sha256::digest(content)));
+    format!("[Binary file: {}]\n", details.join(", "))
+}
+
+/// One-line description of a Git LFS pointer: the MIME type guessed from
+/// the path, and the size and SHA-256 of the object it stands for
+pub fn describe_lfs_pointer(path: &str, pointer: &LfsPointer) -> String {
+    format!(
+        "[Git LFS pointer: {}, {}, sha256 {}]\n",
+        mime_from_extension(path).unwrap_or("application/octet-stream"),
+        ByteSize::b(pointer.size),
+        pointer.oid
+    )
+}
+
+/// MIME type of a file, from its leading bytes and otherwise its extension.
+/// ZIP archives are refined by extension, as many formats are ZIP-based.
+pub fn detect_mime(path: &str, content: &[u8]) -> &'static str {
+    match sniff_mime(content) {
+        Some("application/zip") => mime_from_extension(path).unwrap_or("application/zip"),
+        Some(mime) => mime,
+        None => mime_from_extension(path).unwrap_or("application/octet-stream"),
+    }
+}
+
+/// MIME type from the magic bytes at the start of the content
+fn sniff_mime(content: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"II*\0", "image/tiff"),
+        (b"MM\0*", "image/tiff"),
+        (b"\0\0\x01\0", "image/x-icon"),
+        (b"8BPS", "image/vnd.adobe.photoshop"),
+        (b"BM", "image/bmp"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"PK\x05\x06", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"BZh", "application/x-bzip2"),
+        (b"\xfd7zXZ\0", "application/x-xz"),
+        (b"\x28\xb5\x2f\xfd", "application/zstd"),
+        (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+        (b"Rar!\x1a\x07", "application/vnd.rar"),
+        (b"SQLite format 3\0", "application/vnd.sqlite3"),
+        (b"\x7fELF", "application/x-elf"),
+        (b"MZ", "application/vnd.microsoft.portable-executable"),
+        (b"\xcf\xfa\xed\xfe", "application/x-mach-binary"),
+        (b"\xce\xfa\xed\xfe", "application/x-mach-binary"),
+        (b"\xca\xfe\xba\xbe", "application/java-vm"),
+        (b"\0asm", "application/wasm"),
+        (b"ID3", "audio/mpeg"),
+        (b"fLaC", "audio/flac"),
+        (b"OggS", "audio/ogg"),
+        (b"\x1a\x45\xdf\xa3", "video/webm"),
+        (b"wOFF", "font/woff"),
+        (b"wOF2", "font/woff2"),
+        (b"OTTO", "font/otf"),
+        (b"\0\x01\0\0\0", "font/ttf"),
+    ];
+    if let Some((_, mime)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| content.starts_with(signature))
+    {
+        return Some(mime);
+    }
+    // Containers with the format at an offset
+    if content.len() >= 12 && content.starts_with(b"RIFF") {
+        return match &content[8..12] {
+            b"WEBP" => Some("image/webp"),
+            b"WAVE" => Some("audio/wav"),
+            b"AVI " => Some("video/x-msvideo"),
+            _ => None,
+        };
+    }
+    if content.len() >= 12 && &content[4..8] == b"ftyp" {
+        return match &content[8..12] {
+            b"avif" => Some("image/avif"),
+            b"heic" | b"heix" | b"mif1" => Some("image/heic"),
+            b"qt  " => Some("video/quicktime"),
+            b"M4A " => Some("audio/mp4"),
+            _ => Some("video/mp4"),
+        };
+    }
+    None
+}
+
+/// MIME type commonly used for a file extension
+fn mime_from_extension(path: &str) -> Option<&'static str> {
+    let ext = Path::new(path).extension()?.to_str()?.to_lowercase();
+    let mime = match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" | "jfif" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "tif" | "tiff" => "image/tiff",
+        "avif" => "image/avif",
+        "heic" | "heif" => "image/heic",
+        "svg" => "image/svg+xml",
+        "psd" => "image/vnd.adobe.photoshop",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "ogg" | "oga" | "opus" => "audio/ogg",
+        "m4a" => "audio/mp4",
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "mkv" => "video/x-matroska",
+        "avi" => "video/x-msvideo",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "jar" => "application/java-archive",
+        "apk" => "application/vnd.android.package-archive",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+        "odp" => "application/vnd.oasis.opendocument.presentation",
+        "epub" => "application/epub+zip",
+        "gz" | "tgz" => "application/gzip",
+        "bz2" => "application/x-bzip2",
+        "xz" => "application/x-xz",
+        "zst" => "application/zstd",
+        "7z" => "application/x-7z-compressed",
+        "rar" => "application/vnd.rar",
+        "tar" => "application/x-tar",
+        "wasm" => "application/wasm",
+        "class" => "application/java-vm",
+        "exe" | "dll" => "application/vnd.microsoft.portable-executable",
+        "so" | "o" => "application/x-elf",
+        "db" | "sqlite" | "sqlite3" => "application/vnd.sqlite3",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "glb" => "model/gltf-binary",
+        "stl" => "model/stl",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Width and height of a PNG, GIF, JPEG, BMP or WebP image, read from its
+/// header
+pub fn image_dimensions(content: &[u8]) -> Option<(u32, u32)> {
+    let be16 = |at: usize| {
+        Some(u16::from_be_bytes(
+            content.get(at..at + 2)?.try_into().ok()?,
+        ))
+    };
+    let le16 = |at: usize| {
+        Some(u16::from_le_bytes(
+            content.get(at..at + 2)?.try_into().ok()?,
+        ))
+    };
+    let be32 = |at: usize| {
+        Some(u32::from_be_bytes(
+            content.get(at..at + 4)?.try_into().ok()?,
+        ))
+    };
+    let le32 = |at: usize| {
+        Some(u32::from_le_bytes(
+            content.get(at..at + 4)?.try_into().ok()?,
+        ))
+    };
+    let le24 = |at: usize| Some(le32(at)? & 0xff_ffff);
+
+    match sniff_mime(content)? {
+        // The IHDR chunk comes first
+        "image/png" => Some((be32(16)?, be32(20)?)),
+        "image/gif" => Some((le16(6)?.into(), le16(8)?.into())),
+        // The height is negative for top-down bitmaps
+        "image/bmp" => Some((le32(18)?, (le32(22)? as i32).unsigned_abs())),
+        "image/webp" => match content.get(12..16)? {
+            b"VP8 " => Some(((le16(26)? & 0x3fff).into(), (le16(28)? & 0x3fff).into())),
+            b"VP8L" => {
+                let bits = le32(21)?;
+                Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+            }
+            b"VP8X" => Some((le24(24)? + 1, le24(27)? + 1)),
+            _ => None,
+        },
+        "image/jpeg" => {
+            // Walk the segments up to the start of frame
+            let mut at = 2;
+            loop {
+                while *content.get(at)? != 0xff {
+                    at += 1;
+                }
+                while *content.get(at)? == 0xff {
+                    at += 1;
+                }
+                let marker = *content.get(at)?;
+                at += 1;
+                match marker {
+                    0xd8 | 0x01 | 0xd0..=0xd7 => continue,
+                    0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                        return Some((be16(at + 5)?.into(), be16(at + 3)?.into()));
+                    }
+                    _ => at += usize::from(be16(at)?),
+                }
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{git, models::ProcessedFile};
+use rayon::prelude::*;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::debug;
+
+/// Maximum number of characters of the author name kept in a blame tag
+const MAX_AUTHOR_CHARS: usize = 10;
+
+/// Repository work tree, path within it, HEAD commit and blob id of the
+/// blamed content
+type BlameKey = (PathBuf, String, git2::Oid, git2::Oid);
+
+/// Blame tags shared by the `attach_blame` calls of one run, so a file
+/// reached through several input paths is only blamed once. Blame follows
+/// a path's history, so identical content at different paths is blamed
+/// separately.
+#[derive(Default)]
+pub struct BlameCache {
+    tags: Mutex<HashMap<BlameKey, Arc<Vec<String>>>>,
+    computed: AtomicUsize,
+}
+
+impl BlameCache {
+    /// Number of files blamed so far, not counting cache hits
+    pub fn computed(&self) -> usize {
+        self.computed.load(Ordering::Relaxed)
+    }
+}
+
+/// Attach per-line blame tags to `files`, whose paths are relative to `base_dir`.
+/// Files are blamed in parallel, reusing the results in `cache`.
+pub fn attach_blame(files: &mut [ProcessedFile], base_dir: &Path, cache: &BlameCache) {
+    // A bare file name has an empty parent directory
+    let base_dir = if base_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        base_dir
+    };
+    let (workdir, prefix, head) = match git::open_repository(base_dir).and_then(|repo| {
+        let prefix = git::repo_relative_prefix(&repo, base_dir)?;
+        let head = repo.head().ok()?.peel_to_commit().ok()?.id();
+        Some((repo.workdir()?.to_path_buf(), prefix, head))
+    }) {
+        Some(found) => found,
+        None => return,
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    files.par_iter_mut().for_each_init(
+        || git2::Repository::open(&workdir).ok(),
+        |repo, file| {
+            let repo = match repo {
+                Some(repo) => repo,
+                None => return,
+            };
+            let repo_path = format!("{}{}", prefix, file.rel_path);
+            let Ok(blob_id) =
+                git2::Oid::hash_object(git2::ObjectType::Blob, file.content.as_bytes())
+            else {
+                return;
+            };
+            let key = (workdir.clone(), repo_path, head, blob_id);
+            if let Some(tags) = cached_blame(cache, repo, key, &file.content, now) {
+                file.blame = tags.as_ref().clone();
+            }
+        },
+    );
+}
+
+fn cached_blame(
+    cache: &BlameCache,
+    repo: &git2::Repository,
+    key: BlameKey,
+    content: &str,
+    now: i64,
+) -> Option<Arc<Vec<String>>> {
+    if let Some(tags) = cache.tags.lock().ok()?.get(&key) {
+        return Some(Arc::clone(tags));
+    }
+
+    cache.computed.fetch_add(1, Ordering::Relaxed);
+    let tags = Arc::new(blame_lines(repo, &key.1, content, now)?);
+    if let Ok(mut tags_by_key) = cache.tags.lock() {
+        tags_by_key.insert(key, Arc::clone(&tags));
+    }
+    Some(tags)
+}
+
+/// Compute one blame tag per line of `content`. Lines that differ from HEAD
+/// are attributed to the working tree.
+fn blame_lines(
+    repo: &git2::Repository,
+    repo_path: &str,
+    content: &str,
+    now: i64,
+) -> Option<Vec<String>> {
+    let committed = match repo.blame_file(Path::new(repo_path), None) {
+        Ok(blame) => blame,
+        Err(e) => {
+            debug!("Unable to blame {}: {:?}", repo_path, e);
+            return None;
+        }
+    };
+    let blame = committed.blame_buffer(content.as_bytes()).ok()?;
+
+    let mut tags: Vec<(String, String)> = Vec::new();
+    for line_number in 1..=content.lines().count() {
+        let tag = match blame.get_line(line_number) {
+            Some(hunk) if !hunk.final_commit_id().is_zero() => {
+                let signature = hunk.final_signature();
+                let author = signature
+                    .name()
+                    .and_then(|name| name.split_whitespace().next())
+                    .unwrap_or("unknown")
+                    .chars()
+                    .take(MAX_AUTHOR_CHARS)
+                    .collect::<String>();
+                (format_age(now - signature.when().seconds()), author)
+            }
+            _ => ("-".to_string(), "uncommitted".to_string()),
+        };
+        tags.push(tag);
+    }
+
+    let age_width = tags.iter().map(|(age, _)| age.len()).max().unwrap_or(0);
+    let author_width = tags
+        .iter()
+        .map(|(_, a)| a.chars().count())
+        .max()
+        .unwrap_or(0);
+    Some(
+        tags.into_iter()
+            .map(|(age, author)| {
+                format!(
+                    "{:>age_width$} {:<author_width$}",
+                    age,
+                    author,
+                    age_width = age_width,
+                    author_width = author_width
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Format an age in seconds as a compact string like "5m", "3d", "2mo" or "4y"
+pub fn format_age(seconds: i64) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+    const MONTH: i64 = 30 * DAY;
+    const YEAR: i64 = 365 * DAY;
+
+    let seconds = seconds.max(0);
+    if seconds < MINUTE {
+        "now".to_string()
+    } else if seconds < HOUR {
+        format!("{}m", seconds / MINUTE)
+    } else if seconds < DAY {
+        format!("{}h", seconds / HOUR)
+    } else if seconds < MONTH {
+        format!("{}d", seconds / DAY)
+    } else if seconds < YEAR {
+        format!("{}mo", seconds / MONTH)
+    } else {
+        format!("{}y", seconds / YEAR)
+    }
+}
+
+/// Prefix each line of `content` with its blame tag.
+/// Content is returned unchanged when no tags are available.
+pub fn format_content_with_blame(content: &str, tags: &[String]) -> String {
+    if tags.is_empty() {
+        return content.to_string();
+    }
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match tags.get(i) {
+            Some(tag) => format!("{} | {}", tag, line),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -12,6 +12,8 @@ pub enum FileCategory {
     Configuration,
     /// Documentation files (markdown, rst, docs folder)
     Documentation,
+    /// Generated or minified files (protobuf output, bundles, `*.min.css`)
+    Generated,
     /// Other files that don't fit into above categories
     #[default]
     Other,
@@ -25,6 +27,7 @@ impl FileCategory {
             FileCategory::Test => 10,
             FileCategory::Documentation => 15,
             FileCategory::Source => 20,
+            FileCategory::Generated => 0,
             FileCategory::Other => 1,
         }
     }
@@ -36,6 +39,7 @@ impl FileCategory {
             FileCategory::Test => "test",
             FileCategory::Configuration => "configuration",
             FileCategory::Documentation => "documentation",
+            FileCategory::Generated => "generated",
             FileCategory::Other => "other",
         }
     }
@@ -52,6 +56,9 @@ pub struct CategoryWeights {
     pub configuration: i32,
     /// Priority offset for documentation files
     pub documentation: i32,
+    /// Priority offset for generated files (only used with --include-generated)
+    #[serde(default = "default_generated_weight")]
+    pub generated: i32,
     /// Priority offset for other files
     pub other: i32,
 }
@@ -63,11 +70,16 @@ impl Default for CategoryWeights {
             test: FileCategory::Test.default_priority_offset(),
             configuration: FileCategory::Configuration.default_priority_offset(),
             documentation: FileCategory::Documentation.default_priority_offset(),
+            generated: default_generated_weight(),
             other: FileCategory::Other.default_priority_offset(),
         }
     }
 }
 
+fn default_generated_weight() -> i32 {
+    FileCategory::Generated.default_priority_offset()
+}
+
 impl CategoryWeights {
     /// Get the priority offset for a given category
     pub fn get_offset(&self, category: FileCategory) -> i32 {
@@ -76,11 +88,120 @@ impl CategoryWeights {
             FileCategory::Test => self.test,
             FileCategory::Configuration => self.configuration,
             FileCategory::Documentation => self.documentation,
+            FileCategory::Generated => self.generated,
             FileCategory::Other => self.other,
         }
     }
 }
 
+/// Banners that code generators put at the top of the files they generate.
+/// Generic phrases like "do not edit" are left out, as hand-written files
+/// use them too.
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "<auto-generated",
+    "Generated by the protocol buffer compiler.",
+];
+
+/// File name suffixes of common code generators and bundlers
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".min.js",
+    ".min.css",
+    ".min.mjs",
+    ".bundle.js",
+    ".chunk.js",
+    ".js.map",
+    ".css.map",
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    "_pb.js",
+    "_pb.d.ts",
+    ".g.dart",
+    ".freezed.dart",
+    ".designer.cs",
+    ".g.cs",
+];
+
+/// Number of leading lines searched for a generator marker
+const GENERATED_MARKER_LINES: usize = 10;
+
+/// Extensions of the files that bundlers and minifiers produce
+const MINIFIABLE_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "css"];
+
+/// JS and CSS files at least this large, with a line of at least
+/// `MINIFIED_LONGEST_LINE` bytes and less than `MINIFIED_WHITESPACE_PERCENT`
+/// percent whitespace, are considered minified
+const MINIFIED_MIN_SIZE: usize = 1024;
+const MINIFIED_LONGEST_LINE: usize = 500;
+const MINIFIED_WHITESPACE_PERCENT: usize = 10;
+
+/// Whether a file was generated by a tool or minified: a known generator
+/// file name, a generator banner in a comment near the top, or, for JS and
+/// CSS, long lines with hardly any whitespace.
+pub fn is_generated_file(file_path: &str, content: &[u8]) -> bool {
+    let name = Path::new(file_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_lowercase())
+        .unwrap_or_default();
+    if GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+        || name.contains(".generated.")
+    {
+        return true;
+    }
+
+    let text = String::from_utf8_lossy(&content[..content.len().min(4096)]);
+    let has_marker = text.lines().take(GENERATED_MARKER_LINES).any(|line| {
+        let line = line.trim_start();
+        let is_comment = ["//", "/*", "*", "#", "--", "<!--", ";"]
+            .iter()
+            .any(|marker| line.starts_with(marker));
+        is_comment && (GENERATED_MARKERS.iter().any(|m| line.contains(m)) || is_go_banner(line))
+    });
+    if has_marker {
+        return true;
+    }
+
+    is_minified(&name, content)
+}
+
+/// The banner of generated Go code: `// Code generated <tool>. DO NOT EDIT.`
+fn is_go_banner(line: &str) -> bool {
+    line.strip_prefix("// Code generated ")
+        .is_some_and(|rest| rest.trim_end().ends_with("DO NOT EDIT."))
+}
+
+fn is_minified(name: &str, content: &[u8]) -> bool {
+    let minifiable = Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MINIFIABLE_EXTENSIONS.contains(&ext));
+    if !minifiable || content.len() < MINIFIED_MIN_SIZE {
+        return false;
+    }
+    let longest_line = content.split(|&b| b == b'\n').map(<[u8]>::len).max();
+    let whitespace = content.iter().filter(|b| b.is_ascii_whitespace()).count();
+    longest_line.unwrap_or(0) >= MINIFIED_LONGEST_LINE
+        && whitespace * 100 < content.len() * MINIFIED_WHITESPACE_PERCENT
+}
+
+/// Categorize a Jupyter notebook rendered by `notebook::render_notebook`
+/// by its cells: documentation if most of them are Markdown, source code
+/// otherwise
+pub fn categorize_notebook(rendered: &str) -> FileCategory {
+    let (code, other) = crate::notebook::count_cells(rendered);
+    if other > code {
+        FileCategory::Documentation
+    } else {
+        FileCategory::Source
+    }
+}
+
 /// Categorize a file based on its path and extension using heuristics
 pub fn categorize_file(file_path: &str) -> FileCategory {
     let path = Path::new(file_path);
@@ -180,7 +301,7 @@ fn is_test_file(path_lower: &str, file_name: &str, extension: &Option<String>) -
     // Special cases for specific languages/frameworks
     match extension.as_deref() {
         Some("test") | Some("spec") => return true,
-        Some("js") | Some("ts") | Some("jsx") | Some("tsx") => {
+        Some("js") | Some("ts") | Some("jsx") | Some("tsx")
             if file_name.ends_with(".test.js")
                 || file_name.ends_with(".test.ts")
                 || file_name.ends_with(".spec.js")
@@ -188,29 +309,23 @@ fn is_test_file(path_lower: &str, file_name: &str, extension: &Option<String>) -
                 || file_name.ends_with(".test.jsx")
                 || file_name.ends_with(".test.tsx")
                 || file_name.ends_with(".spec.jsx")
-                || file_name.ends_with(".spec.tsx")
-            {
-                return true;
-            }
+                || file_name.ends_with(".spec.tsx") =>
+        {
+            return true;
         }
-        Some("py") => {
-            if file_name.starts_with("test_") || file_name.ends_with("_test.py") {
-                return true;
-            }
+        Some("py") if file_name.starts_with("test_") || file_name.ends_with("_test.py") => {
+            return true;
         }
-        Some("rs") => {
-            // Rust integration tests
+        // Rust integration tests
+        Some("rs")
             if path_lower.contains("/tests/")
                 || path_lower.contains("\\tests\\")
-                || path_lower.starts_with("tests/")
-            {
-                return true;
-            }
+                || path_lower.starts_with("tests/") =>
+        {
+            return true;
         }
-        Some("java") => {
-            if file_name.ends_with("test.java") || file_name.ends_with("tests.java") {
-                return true;
-            }
+        Some("java") if file_name.ends_with("test.java") || file_name.ends_with("tests.java") => {
+            return true;
         }
         _ => {}
     }
@@ -462,7 +577,8 @@ fn is_source_file(path_lower: &str, extension: &Option<String>) -> bool {
         "m", "mm", "swift", "kt", "java", "dart", // System/Low-level
         "asm", "s", "nasm", "v", "vhd", "vhdl", // Functional
         "clj", "cljs", "hs", "elm", "ml", "fs", // Other
-        "sql", "graphql", "proto", "thrift", "avro",
+        "sql", "graphql", "proto", "thrift", "avro", // Notebooks
+        "ipynb",
     ];
 
     if let Some(ext) = extension {
@@ -594,6 +710,7 @@ mod tests {
             test: 50,
             configuration: 25,
             documentation: 10,
+            generated: 0,
             other: 5,
         };
         assert_eq!(custom_weights.get_offset(FileCategory::Source), 100);
@@ -0,0 +1,216 @@
+use crate::{
+    count_tokens,
+    lang::Language,
+    models::ProcessedFile,
+    snippets::original_line_numbers,
+    symbols::{extend_to_leading_comments, top_level_symbols},
+};
+use rayon::prelude::*;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// One retrieval-sized piece of a file, emitted as a line of `chunks-jsonl`
+#[derive(Debug, Clone, Serialize)]
+pub struct Chunk {
+    pub path: String,
+    /// First line of the chunk in the original file (1-based)
+    pub start_line: usize,
+    /// Last line of the chunk in the original file (inclusive)
+    pub end_line: usize,
+    pub category: &'static str,
+    pub language: Option<&'static str>,
+    /// SHA-256 of the chunk content, hex encoded
+    pub hash: String,
+    pub tokens: usize,
+    pub content: String,
+}
+
+/// Split a file into chunks of at most `max_tokens` tokens.
+///
+/// Chunks start at syntactic boundaries where possible: top-level items
+/// (with their doc comments) in source files and headings in Markdown.
+/// Adjacent sections are merged while they fit, and sections larger than
+/// `max_tokens` are split at blank lines, then into line windows
+/// overlapping by `overlap` lines.
+pub fn chunk_file(file: &ProcessedFile, max_tokens: usize, overlap: usize) -> Vec<Chunk> {
+    let lines: Vec<&str> = file.content.lines().collect();
+    if lines.is_empty() {
+        return Vec::new();
+    }
+    let line_tokens: Vec<usize> = lines.iter().map(|line| count_tokens(line) + 1).collect();
+    let ranges = split_ranges(
+        &file.rel_path,
+        &file.content,
+        &lines,
+        &line_tokens,
+        max_tokens,
+        overlap,
+    );
+
+    let original_numbers = original_line_numbers(file);
+    let language = Language::from_path(&file.rel_path).map(Language::name);
+    ranges
+        .into_iter()
+        .map(|(from, to)| {
+            let mut content = lines[from..to].join("\n");
+            content.push('\n');
+            Chunk {
+                path: file.rel_path.clone(),
+                start_line: original_numbers[from],
+                end_line: original_numbers[to - 1],
+                category: file.category.name(),
+                language,
+                hash: format!("{:x}", Sha256::digest(content.as_bytes())),
+                tokens: count_tokens(&content),
+                content,
+            }
+        })
+        .collect()
+}
+
+/// Render the chunks of all files as JSON Lines, in file order
+pub fn chunks_jsonl(
+    files: &[&ProcessedFile],
+    max_tokens: usize,
+    overlap: usize,
+) -> anyhow::Result<String> {
+    let chunks: Vec<Vec<Chunk>> = files
+        .par_iter()
+        .map(|file| chunk_file(file, max_tokens, overlap))
+        .collect();
+    let mut output = String::new();
+    for chunk in chunks.iter().flatten() {
+        output.push_str(&serde_json::to_string(chunk)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Split `lines` into half-open ranges whose summed `line_costs` stay
+/// within `max_cost`. Ranges start at top-level items or Markdown headings
+/// where possible; oversized sections are split at blank lines, and what is
+/// still too large into line windows overlapping by `overlap` lines.
+pub(crate) fn split_ranges(
+    path: &str,
+    content: &str,
+    lines: &[&str],
+    line_costs: &[usize],
+    max_cost: usize,
+    overlap: usize,
+) -> Vec<(usize, usize)> {
+    let sections = section_starts(path, content, lines);
+    pack(
+        &sections,
+        lines.len(),
+        line_costs,
+        max_cost,
+        &mut |start, end| {
+            let paragraphs = paragraph_starts(lines, start, end);
+            pack(&paragraphs, end, line_costs, max_cost, &mut |from, to| {
+                line_windows(&line_costs[from..to], max_cost, overlap)
+                    .into_iter()
+                    .map(|(a, b)| (from + a, from + b))
+                    .collect()
+            })
+        },
+    )
+}
+
+/// Merge consecutive sections (starting at `starts`, the last one ending at
+/// `end`) while their cost fits; sections that are too large on their own
+/// are handed to `split`
+fn pack(
+    starts: &[usize],
+    end: usize,
+    costs: &[usize],
+    max_cost: usize,
+    split: &mut dyn FnMut(usize, usize) -> Vec<(usize, usize)>,
+) -> Vec<(usize, usize)> {
+    let cost_between = |from: usize, to: usize| costs[from..to].iter().sum::<usize>();
+    let ends = starts.iter().skip(1).copied().chain([end]);
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    for (start, end) in starts.iter().copied().zip(ends) {
+        if let Some((from, to)) = current {
+            if cost_between(from, end) <= max_cost {
+                current = Some((from, end));
+                continue;
+            }
+            ranges.push((from, to));
+        }
+        if cost_between(start, end) <= max_cost {
+            current = Some((start, end));
+        } else {
+            ranges.extend(split(start, end));
+            current = None;
+        }
+    }
+    ranges.extend(current);
+    ranges
+}
+
+/// Lines in `start..end` that begin a paragraph, i.e. follow a blank line;
+/// always includes `start`
+fn paragraph_starts(lines: &[&str], start: usize, end: usize) -> Vec<usize> {
+    let mut starts = vec![start];
+    starts.extend(
+        (start + 1..end).filter(|&i| lines[i - 1].trim().is_empty() && !lines[i].trim().is_empty()),
+    );
+    starts
+}
+
+/// Line indices where a new section starts; always includes line 0
+fn section_starts(path: &str, content: &str, lines: &[&str]) -> Vec<usize> {
+    let mut starts = vec![0];
+    let is_markdown = path.ends_with(".md") || path.ends_with(".markdown");
+    if is_markdown {
+        let mut in_fence = false;
+        for (index, line) in lines.iter().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            } else if !in_fence && is_heading(trimmed) {
+                starts.push(index);
+            }
+        }
+    } else if let Some(language) = Language::from_path(path) {
+        starts.extend(
+            top_level_symbols(path, content)
+                .into_iter()
+                .map(|symbol| extend_to_leading_comments(lines, symbol.line - 1, language)),
+        );
+    }
+    starts.sort_unstable();
+    starts.dedup();
+    starts
+}
+
+/// Whether a line is an ATX heading such as `## Usage`
+fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with([' ', '\t'])
+}
+
+/// Split lines into windows of at most `max_tokens` tokens (at least one
+/// line each), where each window repeats the last `overlap` lines of the
+/// previous one (no more than half of it). Returns half-open ranges of line indices.
+fn line_windows(line_tokens: &[usize], max_tokens: usize, overlap: usize) -> Vec<(usize, usize)> {
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while start < line_tokens.len() {
+        let mut end = start + 1;
+        let mut tokens = line_tokens[start];
+        while end < line_tokens.len() && tokens + line_tokens[end] <= max_tokens {
+            tokens += line_tokens[end];
+            end += 1;
+        }
+        windows.push((start, end));
+        if end == line_tokens.len() {
+            break;
+        }
+        // Repeat at most half of the window, so windows of long lines still advance
+        start = end - overlap.min((end - start) / 2);
+    }
+    windows
+}
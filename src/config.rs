@@ -86,6 +86,10 @@ pub struct YekConfig {
     #[config_arg(accept_from = "config_only")]
     pub git_boost_max: Option<i32>,
 
+    /// Maximum priority boost for the file that best matches --query
+    #[config_arg(accept_from = "config_only")]
+    pub query_boost_max: Option<i32>,
+
     /// Category-based priority weights
     #[config_arg(accept_from = "config_only")]
     pub category_weights: Option<crate::category::CategoryWeights>,
@@ -98,6 +102,168 @@ pub struct YekConfig {
     #[config_arg(long = "tree-only")]
     pub tree_only: bool,
 
+    /// Output format: "chunks-jsonl" splits files into retrieval-sized chunks, one JSON record per line
+    #[config_arg(long = "format")]
+    pub format: Option<String>,
+
+    /// Maximum number of tokens per chunk with --format chunks-jsonl
+    #[config_arg(long = "chunk-tokens", default_value = "512")]
+    pub chunk_tokens: usize,
+
+    /// Number of lines repeated between consecutive chunks when a section is split into line windows
+    #[config_arg(long = "chunk-overlap", default_value = "5")]
+    pub chunk_overlap: usize,
+
+    /// Include a repository map of top-level symbols per file, ranked by references (incompatible with JSON output)
+    #[config_arg(long = "repo-map")]
+    pub repo_map: bool,
+
+    /// Maximum share of the size or token budget used by the repository map, in percent
+    #[config_arg(long = "repo-map-share", default_value = "10")]
+    pub repo_map_share: usize,
+
+    /// Also list files left out by the budget in the repository map
+    #[config_arg(long = "repo-map-excluded")]
+    pub repo_map_excluded: bool,
+
+    /// Include git metadata header (branch, HEAD, worktree status, recent commits)
+    #[config_arg(long = "git-header")]
+    pub git_header: bool,
+
+    /// Number of recent commit subjects to list in the git header
+    #[config_arg(long = "git-log-count", default_value = "5")]
+    pub git_log_count: usize,
+
+    /// Attach the last N commits touching each file (0 disables)
+    #[config_arg(long = "file-history")]
+    pub file_history: usize,
+
+    /// Prefix each line with the age and author of its last change (git blame)
+    #[config_arg(long = "blame")]
+    pub blame: bool,
+
+    /// Annotate the tree and files with git status markers (M, A, D, ??, R). Use FILE_STATUS in the output template
+    #[config_arg(long = "git-status")]
+    pub git_status: bool,
+
+    /// Select files touched by commits whose message matches this regex (e.g. a ticket ID)
+    #[config_arg(long = "commits-matching")]
+    pub commits_matching: Option<String>,
+
+    /// Only search commits newer than this date (YYYY-MM-DD) or age (e.g. 30d, 2w, 3mo, 1y)
+    #[config_arg(long = "since")]
+    pub since: Option<String>,
+
+    /// Boost files touched by matching commits by this score instead of including only those files
+    #[config_arg(long = "commits-boost")]
+    pub commits_boost: Option<i32>,
+
+    /// Serialize the version history of a single file instead of the input paths
+    #[config_arg(long = "history")]
+    pub history: Option<String>,
+
+    /// Number of distinct versions to include with --history
+    #[config_arg(long = "versions", default_value = "5")]
+    pub versions: usize,
+
+    /// Seed files whose transitive imports (Rust, JS/TS, Python, Go) are included
+    #[config_arg(long = "follow-imports", multi_value_behavior = "extend")]
+    pub follow_imports: Vec<String>,
+
+    /// Maximum number of import hops followed from the seed files
+    #[config_arg(long = "import-depth", default_value = "3")]
+    pub import_depth: usize,
+
+    /// Also include files that import the seed files (change-impact analysis)
+    #[config_arg(long = "reverse-imports")]
+    pub reverse_imports: bool,
+
+    /// Rank files by relevance to this search query (BM25 over paths and contents)
+    #[config_arg(long = "query")]
+    pub query: Option<String>,
+
+    /// Only include lines matching this regex, with surrounding context; files without a match are dropped
+    #[config_arg(long = "grep")]
+    pub grep: Option<String>,
+
+    /// Number of context lines shown around each --grep match
+    #[config_arg(long = "context", default_value = "3")]
+    pub context: usize,
+
+    /// Reduce source files to signatures and doc comments, with bodies replaced by { ... }
+    #[config_arg(long = "outline")]
+    pub outline: bool,
+
+    /// Outline only files whose priority is below this value
+    #[config_arg(long = "outline-below")]
+    pub outline_below: Option<i32>,
+
+    /// Strip license headers repeated across files and mention each license once in the output header
+    #[config_arg(long = "strip-license-headers")]
+    pub strip_license_headers: bool,
+
+    /// Minify source files: 1 drops trailing whitespace and blank lines, 2 also strips comments
+    #[config_arg(long = "minify")]
+    pub minify: Option<u8>,
+
+    /// Keep doc comments when stripping comments with --minify 2
+    #[config_arg(long = "keep-doc-comments")]
+    pub keep_doc_comments: bool,
+
+    /// Include identical files only once; other copies are listed as "identical to X"
+    #[config_arg(long = "dedupe")]
+    pub dedupe: bool,
+
+    /// Also treat files at least this similar (in percent, SimHash) as duplicates. Implies --dedupe
+    #[config_arg(long = "dedupe-similarity")]
+    pub dedupe_similarity: Option<u8>,
+
+    /// Split files larger than this (a size, or a token count in token mode) into parts at item or blank-line boundaries
+    #[config_arg(long = "split-size")]
+    pub split_size: Option<String>,
+
+    /// Include generated and minified files (skipped by default) with the priority offset of the "generated" category
+    #[config_arg(long = "include-generated")]
+    pub include_generated: bool,
+
+    /// Replace Cargo.lock, package-lock.json, yarn.lock, poetry.lock and go.sum with a list of their direct and transitive packages
+    #[config_arg(long = "summarize-lockfiles")]
+    pub summarize_lockfiles: bool,
+
+    /// Replace JSON, YAML, CSV and NDJSON files larger than this size with their schema, record count and first records
+    #[config_arg(long = "summarize-data")]
+    pub summarize_data: Option<String>,
+
+    /// Number of records or rows kept by --summarize-data
+    #[config_arg(long = "data-sample", default_value = "3")]
+    pub data_sample: usize,
+
+    /// Lines of text output kept per output of a Jupyter notebook cell (0 drops outputs)
+    #[config_arg(long = "notebook-outputs", default_value = "0")]
+    pub notebook_outputs: usize,
+
+    /// Include the text of PDF, DOCX and ODT documents instead of skipping them as binary
+    #[config_arg(long = "extract-documents")]
+    pub extract_documents: bool,
+
+    /// Maximum size of the text extracted from each document; the rest is cut off
+    #[config_arg(long = "document-max-size", default_value = "256KB")]
+    pub document_max_size: String,
+
+    /// Include SQLite databases as their schema and row counts instead of skipping them as binary
+    #[cfg(feature = "sqlite")]
+    #[config_arg(long = "sqlite-schema")]
+    pub sqlite_schema: bool,
+
+    /// Number of rows shown per table by --sqlite-schema
+    #[cfg(feature = "sqlite")]
+    #[config_arg(long = "sqlite-rows", default_value = "0")]
+    pub sqlite_rows: usize,
+
+    /// Include binary files as a one-line placeholder with their type, size and SHA-256
+    #[config_arg(long = "binary-placeholders")]
+    pub binary_placeholders: bool,
+
     /// True if we should stream output to stdout (computed)
     pub stream: bool,
 
@@ -135,11 +301,54 @@ impl Default for YekConfig {
                 .map(|s| s.to_string())
                 .collect(),
             git_boost_max: Some(100),
+            query_boost_max: Some(1000),
             category_weights: None,
 
             // computed fields
             tree_header: false,
             tree_only: false,
+            format: None,
+            chunk_tokens: 512,
+            chunk_overlap: 5,
+            repo_map: false,
+            repo_map_share: 10,
+            repo_map_excluded: false,
+            git_header: false,
+            git_log_count: 5,
+            file_history: 0,
+            blame: false,
+            git_status: false,
+            commits_matching: None,
+            since: None,
+            commits_boost: None,
+            history: None,
+            versions: 5,
+            follow_imports: Vec::new(),
+            import_depth: 3,
+            reverse_imports: false,
+            query: None,
+            grep: None,
+            context: 3,
+            outline: false,
+            outline_below: None,
+            strip_license_headers: false,
+            minify: None,
+            keep_doc_comments: false,
+            dedupe: false,
+            dedupe_similarity: None,
+            split_size: None,
+            include_generated: false,
+            summarize_lockfiles: false,
+            summarize_data: None,
+            data_sample: 3,
+            notebook_outputs: 0,
+            extract_documents: false,
+            document_max_size: "256KB".to_string(),
+            #[cfg(feature = "sqlite")]
+            sqlite_schema: false,
+            #[cfg(feature = "sqlite")]
+            sqlite_rows: 0,
+            binary_placeholders: false,
             stream: false,
             token_mode: false,
             output_file_full_path: None,
@@ -234,8 +443,8 @@ impl YekConfig {
             cfg.output_template = Some(DEFAULT_OUTPUT_TEMPLATE.to_string());
         }
 
-        // Check if we should read input paths from stdin
-        if cfg.input_paths.is_empty() {
+        // Check if we should read input paths from stdin (history mode takes no inputs)
+        if cfg.input_paths.is_empty() && cfg.history.is_none() {
             if !std::io::stdin().is_terminal() {
                 // Read file paths from stdin (one per line)
                 match cfg.read_input_paths_from_stdin() {
@@ -271,8 +480,13 @@ impl YekConfig {
             .collect();
 
         // Always start with default ignore patterns, then add user's:
+        // Summarized lockfiles are no longer ignored by default
         let mut ignore = DEFAULT_IGNORE_PATTERNS
             .iter()
+            .filter(|pattern| {
+                !(cfg.summarize_lockfiles
+                    && crate::lockfile::lockfile_names().any(|name| name == **pattern))
+            })
             .map(|s| s.to_string())
             .collect::<Vec<_>>();
         ignore.extend(cfg.ignore_patterns);
@@ -363,6 +577,41 @@ impl YekConfig {
         hex[..8].to_owned()
     }
 
+    /// The --split-size limit in bytes, or in tokens in token mode
+    pub fn split_limit(&self) -> Result<Option<usize>> {
+        let Some(split_size) = &self.split_size else {
+            return Ok(None);
+        };
+        let limit = if self.token_mode {
+            crate::parse_token_limit(split_size)
+                .map_err(|e| anyhow!("split_size: Invalid token size: {}", e))?
+        } else {
+            ByteSize::from_str(split_size)
+                .map_err(|e| anyhow!("split_size: Invalid size format: {}", e))?
+                .as_u64() as usize
+        };
+        Ok(Some(limit))
+    }
+
+    /// Size in bytes above which data files are summarized, if enabled
+    pub fn summarize_data_limit(&self) -> Result<Option<usize>> {
+        self.summarize_data
+            .as_ref()
+            .map(|size| {
+                ByteSize::from_str(size)
+                    .map(|size| size.as_u64() as usize)
+                    .map_err(|e| anyhow!("summarize_data: Invalid size format: {}", e))
+            })
+            .transpose()
+    }
+
+    /// Maximum size in bytes of the text extracted from a document
+    pub fn document_size_limit(&self) -> Result<usize> {
+        ByteSize::from_str(&self.document_max_size)
+            .map(|size| size.as_u64() as usize)
+            .map_err(|e| anyhow!("document_max_size: Invalid size format: {}", e))
+    }
+
     /// Validate the final config.
     pub fn validate(&self) -> Result<()> {
         let template = self
@@ -415,6 +664,16 @@ impl YekConfig {
             self.ensure_output_dir()?;
         }
 
+        // Validate line ranges in input paths (path:START-END or path:START+COUNT)
+        for path in &self.input_paths {
+            if crate::selection::has_invalid_line_range(path) {
+                return Err(anyhow!(
+                    "input_paths: Invalid line range in '{}', expected path:START-END or path:START+COUNT",
+                    path
+                ));
+            }
+        }
+
         // Validate ignore patterns
         for pattern in &self.ignore_patterns {
             glob::Pattern::new(pattern)
@@ -434,6 +693,30 @@ impl YekConfig {
             })?;
         }
 
+        // Validate commit message search
+        if let Some(pattern) = &self.commits_matching {
+            regex::Regex::new(pattern)
+                .map_err(|e| anyhow!("commits_matching: Invalid regex '{}': {}", pattern, e))?;
+        } else if self.since.is_some() || self.commits_boost.is_some() {
+            return Err(anyhow!(
+                "commits_matching: required when using since or commits_boost"
+            ));
+        }
+        if let Some(since) = &self.since {
+            crate::priority::parse_since(since, 0)?;
+        }
+
+        // Validate grep pattern
+        if let Some(pattern) = &self.grep {
+            regex::Regex::new(pattern)
+                .map_err(|e| anyhow!("grep: Invalid regex '{}': {}", pattern, e))?;
+        }
+
+        // Validate history mode
+        if self.history.is_some() && self.versions == 0 {
+            return Err(anyhow!("versions: cannot be 0"));
+        }
+
         // Validate tree options are mutually exclusive
         if self.tree_header && self.tree_only {
             return Err(anyhow!("tree_header and tree_only cannot both be enabled"));
@@ -448,6 +731,59 @@ impl YekConfig {
             return Err(anyhow!("JSON output not supported in tree-only mode"));
         }
 
+        if self.json && self.repo_map {
+            return Err(anyhow!("JSON output not supported with repository map"));
+        }
+
+        // Validate minify level
+        if let Some(level) = self.minify {
+            if !(1..=2).contains(&level) {
+                return Err(anyhow!("minify: must be 1 or 2, got {}", level));
+            }
+        }
+
+        if let Some(similarity) = self.dedupe_similarity {
+            if !(1..=100).contains(&similarity) {
+                return Err(anyhow!(
+                    "dedupe_similarity: must be between 1 and 100, got {}",
+                    similarity
+                ));
+            }
+        }
+
+        // Validate split size
+        if self.split_limit()? == Some(0) {
+            return Err(anyhow!("split_size: cannot be 0"));
+        }
+
+        // Validate data summary and document sizes
+        self.summarize_data_limit()?;
+        if self.document_size_limit()? == 0 {
+            return Err(anyhow!("document_max_size: cannot be 0"));
+        }
+
+        // Validate output format
+        if let Some(format) = &self.format {
+            if format != "chunks-jsonl" {
+                return Err(anyhow!(
+                    "format: Unknown format '{}', expected chunks-jsonl",
+                    format
+                ));
+            }
+            if self.json || self.tree_header || self.tree_only || self.repo_map {
+                return Err(anyhow!(
+                    "format: chunks-jsonl cannot be combined with JSON output, tree or repository map headers"
+                ));
+            }
+            if self.chunk_tokens == 0 {
+                return Err(anyhow!("chunk_tokens: cannot be 0"));
+            }
+        }
+
+        if self.repo_map_share == 0 || self.repo_map_share > 100 {
+            return Err(anyhow!("repo_map_share: must be between 1 and 100"));
+        }
+
         Ok(())
     }
 
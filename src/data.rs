@@ -0,0 +1,442 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+/// Fields listed per object in a schema; the rest are counted
+const MAX_FIELDS: usize = 40;
+/// Nesting levels shown in a schema
+const MAX_DEPTH: usize = 6;
+/// Characters kept of each example value and sample record
+const MAX_EXAMPLE: usize = 60;
+const MAX_RECORD: usize = 1000;
+
+/// Structured data formats that can be summarized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Json,
+    Ndjson,
+    Yaml,
+    Csv,
+    Tsv,
+}
+
+impl DataFormat {
+    /// Detect the format from a file path's extension
+    pub fn from_path(path: &str) -> Option<DataFormat> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_lowercase())?;
+        match extension.as_str() {
+            "json" => Some(DataFormat::Json),
+            "ndjson" | "jsonl" => Some(DataFormat::Ndjson),
+            "yaml" | "yml" => Some(DataFormat::Yaml),
+            "csv" => Some(DataFormat::Csv),
+            "tsv" => Some(DataFormat::Tsv),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            DataFormat::Json => "JSON",
+            DataFormat::Ndjson => "NDJSON",
+            DataFormat::Yaml => "YAML",
+            DataFormat::Csv => "CSV",
+            DataFormat::Tsv => "TSV",
+        }
+    }
+}
+
+/// The inferred shape of the values seen at one place in the data
+#[derive(Debug, Default)]
+struct Shape {
+    /// Number of values seen
+    count: usize,
+    /// Number of those values that were objects
+    objects: usize,
+    types: BTreeSet<&'static str>,
+    /// First non-null scalar value
+    example: Option<String>,
+    /// Fields of objects, in order of first appearance
+    fields: Vec<(String, Shape)>,
+    /// Position of each field in `fields`
+    field_index: HashMap<String, usize>,
+    /// Elements of arrays
+    items: Option<Box<Shape>>,
+}
+
+impl Shape {
+    fn observe(&mut self, value: &Value) {
+        self.count += 1;
+        match value {
+            Value::Null => {
+                self.types.insert("null");
+            }
+            Value::Bool(_) => self.observe_scalar("boolean", value.to_string()),
+            Value::Number(n) if n.is_f64() => self.observe_scalar("number", value.to_string()),
+            Value::Number(_) => self.observe_scalar("integer", value.to_string()),
+            Value::String(_) => self.observe_scalar("string", value.to_string()),
+            Value::Array(items) => {
+                self.types.insert("array");
+                let shape = self.items.get_or_insert_with(Box::default);
+                for item in items {
+                    shape.observe(item);
+                }
+            }
+            Value::Object(fields) => {
+                self.types.insert("object");
+                self.objects += 1;
+                for (key, value) in fields {
+                    let index = match self.field_index.get(key) {
+                        Some(&index) => index,
+                        None => {
+                            self.field_index.insert(key.clone(), self.fields.len());
+                            self.fields.push((key.clone(), Shape::default()));
+                            self.fields.len() - 1
+                        }
+                    };
+                    self.fields[index].1.observe(value);
+                }
+            }
+        }
+    }
+
+    fn observe_scalar(&mut self, kind: &'static str, example: String) {
+        self.types.insert(kind);
+        if self.example.is_none() {
+            self.example = Some(truncate(&example, MAX_EXAMPLE));
+        }
+    }
+
+    /// Type description such as `string | null` or `array of object`
+    fn describe(&self) -> String {
+        let types: Vec<String> = self
+            .types
+            .iter()
+            .map(|&kind| match (kind, &self.items) {
+                ("array", Some(items)) if !items.types.is_empty() => {
+                    format!("array of {}", items.describe())
+                }
+                _ => kind.to_string(),
+            })
+            .collect();
+        let mut description = types.join(" | ");
+        if let Some(example) = &self.example {
+            description.push_str(&format!(" (e.g. {})", example));
+        }
+        description
+    }
+
+    /// Fields of this shape's objects, or of the objects in its arrays
+    fn nested(&self) -> Option<&Shape> {
+        if !self.fields.is_empty() {
+            Some(self)
+        } else {
+            self.items.as_deref().and_then(Shape::nested)
+        }
+    }
+
+    /// Render the fields of the objects described by this shape, marking
+    /// fields missing from some objects with `?`
+    fn render_fields(&self, depth: usize, output: &mut String) {
+        let indent = "  ".repeat(depth + 1);
+        if depth >= MAX_DEPTH {
+            output.push_str(&format!("{}...\n", indent));
+            return;
+        }
+        for (key, field) in self.fields.iter().take(MAX_FIELDS) {
+            let optional = if field.count < self.objects { "?" } else { "" };
+            output.push_str(&format!(
+                "{}{}{}: {}\n",
+                indent,
+                key,
+                optional,
+                field.describe()
+            ));
+            if let Some(nested) = field.nested() {
+                nested.render_fields(depth + 1, output);
+            }
+        }
+        if self.fields.len() > MAX_FIELDS {
+            output.push_str(&format!(
+                "{}... {} more fields\n",
+                indent,
+                self.fields.len() - MAX_FIELDS
+            ));
+        }
+    }
+}
+
+/// Replace a large data file with its inferred schema, the first `sample`
+/// records and the total record count. JSON arrays, the largest array in
+/// a JSON object, NDJSON lines, YAML documents or sequences, and CSV/TSV
+/// rows count as records. Returns `None` if the file is not in a supported
+/// format or can't be parsed.
+pub fn summarize_data(path: &str, content: &str, sample: usize) -> Option<String> {
+    let format = DataFormat::from_path(path)?;
+    match format {
+        DataFormat::Csv => summarize_table(content, ',', sample, format),
+        DataFormat::Tsv => summarize_table(content, '\t', sample, format),
+        DataFormat::Json => {
+            let value: Value = serde_json::from_str(content).ok()?;
+            Some(summarize_value(value, sample, format))
+        }
+        DataFormat::Yaml => {
+            let mut documents = serde_yaml::Deserializer::from_str(content)
+                .map(Value::deserialize)
+                .collect::<Result<Vec<Value>, _>>()
+                .ok()?;
+            let value = if documents.len() == 1 {
+                documents.pop()?
+            } else {
+                Value::Array(documents)
+            };
+            Some(summarize_value(value, sample, format))
+        }
+        DataFormat::Ndjson => {
+            let records = content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<Vec<Value>, _>>()
+                .ok()?;
+            Some(summarize_records(&records, None, sample, format))
+        }
+    }
+}
+
+/// Summarize a parsed JSON or YAML document
+fn summarize_value(value: Value, sample: usize, format: DataFormat) -> String {
+    match value {
+        Value::Array(records) => summarize_records(&records, None, sample, format),
+        Value::Object(fields) => {
+            // Records are the elements of the largest array, if there is one
+            let largest = fields
+                .iter()
+                .filter_map(|(key, value)| Some((key, value.as_array()?)))
+                .max_by_key(|(_, records)| records.len());
+            match largest {
+                Some((key, records)) if records.len() > 1 => {
+                    let mut rest = fields.clone();
+                    rest.remove(key);
+                    let mut summary = summarize_records(records, Some(key), sample, format);
+                    if !rest.is_empty() {
+                        let mut shape = Shape::default();
+                        shape.observe(&Value::Object(rest));
+                        summary.push_str("other fields:\n");
+                        shape.render_fields(0, &mut summary);
+                    }
+                    summary
+                }
+                _ => {
+                    // An object keyed by name: each entry is a record
+                    let records: Vec<Value> = fields
+                        .into_iter()
+                        .map(|(key, value)| {
+                            let mut entry = serde_json::Map::new();
+                            entry.insert(key, value);
+                            Value::Object(entry)
+                        })
+                        .collect();
+                    summarize_entries(&records, sample, format)
+                }
+            }
+        }
+        scalar => {
+            let mut shape = Shape::default();
+            shape.observe(&scalar);
+            format!(
+                "{} data summary: a single {}\n",
+                format.name(),
+                shape.describe()
+            )
+        }
+    }
+}
+
+/// Summarize a list of records: their count, schema and the first ones
+fn summarize_records(
+    records: &[Value],
+    key: Option<&str>,
+    sample: usize,
+    format: DataFormat,
+) -> String {
+    let mut shape = Shape::default();
+    for record in records {
+        shape.observe(record);
+    }
+    let location = key
+        .map(|key| format!(" in \"{}\"", key))
+        .unwrap_or_default();
+    let mut output = format!(
+        "{} data summary: {} records{}, showing the schema and the first {}\n",
+        format.name(),
+        records.len(),
+        location,
+        sample.min(records.len())
+    );
+    output.push_str("schema:\n");
+    if shape.fields.is_empty() {
+        output.push_str(&format!("  record: {}\n", shape.describe()));
+        if let Some(nested) = shape.nested() {
+            nested.render_fields(1, &mut output);
+        }
+    } else {
+        shape.render_fields(0, &mut output);
+    }
+    push_samples(
+        "first records:\n",
+        records.iter().take(sample).map(|record| record.to_string()),
+        &mut output,
+    );
+    output
+}
+
+/// Summarize the entries of an object keyed by name, such as a map of
+/// users by id. Entries are described by the shape of their values.
+fn summarize_entries(entries: &[Value], sample: usize, format: DataFormat) -> String {
+    let mut shape = Shape::default();
+    for entry in entries {
+        if let Some(value) = entry.as_object().and_then(|e| e.values().next()) {
+            shape.observe(value);
+        }
+    }
+    let mut output = format!(
+        "{} data summary: object with {} keys, showing the schema of the values and the first {}\n",
+        format.name(),
+        entries.len(),
+        sample.min(entries.len())
+    );
+    output.push_str("schema:\n");
+    output.push_str(&format!("  <key>: {}\n", shape.describe()));
+    if let Some(nested) = shape.nested() {
+        nested.render_fields(1, &mut output);
+    }
+    push_samples(
+        "first entries:\n",
+        entries.iter().take(sample).map(|entry| entry.to_string()),
+        &mut output,
+    );
+    output
+}
+
+fn push_samples(title: &str, samples: impl Iterator<Item = String>, output: &mut String) {
+    output.push_str(title);
+    for sample in samples {
+        output.push_str(&truncate(&sample, MAX_RECORD));
+        output.push('\n');
+    }
+}
+
+/// Summarize a CSV or TSV table: its columns with inferred types and
+/// examples, the row count, and the header with the first rows as written
+fn summarize_table(
+    content: &str,
+    delimiter: char,
+    sample: usize,
+    format: DataFormat,
+) -> Option<String> {
+    let records = split_records(content);
+    let (header, rows) = records.split_first()?;
+    let columns = split_fields(header, delimiter);
+    let mut shapes: Vec<Shape> = columns.iter().map(|_| Shape::default()).collect();
+    for row in rows {
+        for (shape, field) in shapes.iter_mut().zip(split_fields(row, delimiter)) {
+            shape.observe(&infer_scalar(&field));
+        }
+    }
+
+    let mut output = format!(
+        "{} data summary: {} rows, {} columns, showing the schema and the first {}\n",
+        format.name(),
+        rows.len(),
+        columns.len(),
+        sample.min(rows.len())
+    );
+    output.push_str("schema:\n");
+    for (column, shape) in columns.iter().zip(&shapes) {
+        output.push_str(&format!("  {}: {}\n", column, shape.describe()));
+    }
+    output.push_str("first rows:\n");
+    for record in std::iter::once(header).chain(rows.iter().take(sample)) {
+        output.push_str(&truncate(record, MAX_RECORD));
+        output.push('\n');
+    }
+    Some(output)
+}
+
+/// Records of a CSV file as written, keeping line breaks inside quotes
+fn split_records(content: &str) -> Vec<&str> {
+    let mut records = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in content.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '\n' if !quoted => {
+                let record = content[start..i].trim_end_matches('\r');
+                if !record.is_empty() {
+                    records.push(record);
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let record = content[start..].trim_end_matches('\r');
+    if !record.is_empty() {
+        records.push(record);
+    }
+    records
+}
+
+/// Fields of a CSV record, unquoted
+fn split_fields(record: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = record.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// The JSON value a CSV field most likely holds
+fn infer_scalar(field: &str) -> Value {
+    let field = field.trim();
+    if field.is_empty() {
+        return Value::Null;
+    }
+    if let Ok(integer) = field.parse::<i64>() {
+        return Value::from(integer);
+    }
+    if let Ok(number) = field.parse::<f64>() {
+        if number.is_finite() {
+            return Value::from(number);
+        }
+    }
+    match field.to_lowercase().as_str() {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(field.to_string()),
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}...", &text[..index]),
+        None => text.to_string(),
+    }
+}
@@ -0,0 +1,161 @@
+use crate::models::{Duplicate, ProcessedFile};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Number of consecutive words hashed together as one SimHash feature
+const SHINGLE_WORDS: usize = 3;
+
+/// Replace duplicate files with a reference to a representative. Files are
+/// visited from the highest priority down, so the representative is the
+/// most important copy. With `similarity` (in percent), files whose
+/// SimHash is at least that similar to a representative's also count as
+/// duplicates. Empty files are never deduplicated. Returns the number of
+/// files replaced.
+pub fn dedupe_files(files: &mut [ProcessedFile], similarity: Option<u8>) -> usize {
+    let fingerprints: Vec<u64> = if similarity.is_some() {
+        files
+            .par_iter()
+            .map(|file| simhash(&file.content))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut order: Vec<usize> = (0..files.len()).collect();
+    order.sort_by(|&a, &b| {
+        files[b]
+            .priority
+            .cmp(&files[a].priority)
+            .then_with(|| files[a].rel_path.cmp(&files[b].rel_path))
+    });
+
+    let bands = similarity.map(lsh_bands).unwrap_or_default();
+    let mut by_content: HashMap<&str, usize> = HashMap::new();
+    let mut representatives: Vec<usize> = Vec::new();
+    // Representatives (by position in `representatives`) per band value
+    let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+    let mut duplicates: Vec<(usize, Duplicate)> = Vec::new();
+    for index in order {
+        let file = &files[index];
+        if file.content.trim().is_empty() {
+            continue;
+        }
+        if let Some(&original) = by_content.get(file.content.as_str()) {
+            duplicates.push((
+                index,
+                Duplicate {
+                    of: files[original].rel_path.clone(),
+                    similarity: 100,
+                },
+            ));
+            continue;
+        }
+        let similar = similarity.and_then(|threshold| {
+            let mut candidates: Vec<usize> = bands
+                .iter()
+                .enumerate()
+                .filter_map(|(band, &range)| {
+                    buckets.get(&(band, band_value(fingerprints[index], range)))
+                })
+                .flatten()
+                .copied()
+                .collect();
+            // Compare in the order the representatives were chosen
+            candidates.sort_unstable();
+            candidates.dedup();
+            candidates.into_iter().find_map(|position| {
+                let original = representatives[position];
+                let percent = similarity_percent(
+                    fingerprints[index],
+                    fingerprints[original],
+                    file.content.len(),
+                    files[original].content.len(),
+                );
+                (percent >= threshold).then_some((original, percent))
+            })
+        });
+        match similar {
+            Some((original, percent)) => duplicates.push((
+                index,
+                Duplicate {
+                    of: files[original].rel_path.clone(),
+                    similarity: percent.min(99),
+                },
+            )),
+            None => {
+                by_content.insert(file.content.as_str(), index);
+                for (band, &range) in bands.iter().enumerate() {
+                    buckets
+                        .entry((band, band_value(fingerprints[index], range)))
+                        .or_default()
+                        .push(representatives.len());
+                }
+                representatives.push(index);
+            }
+        }
+    }
+
+    let count = duplicates.len();
+    for (index, duplicate) in duplicates {
+        let file = &mut files[index];
+        file.replace_content(String::new());
+        file.blame.clear();
+        file.windows.clear();
+        file.duplicate = Some(duplicate);
+    }
+    count
+}
+
+/// Bit ranges of the fingerprint used as locality-sensitive hashing bands.
+/// Fingerprints similar enough to reach `threshold` differ in at most
+/// `max_distance` bits, so they agree on at least one of `max_distance + 1`
+/// bands: comparing only files that share a band finds every match.
+fn lsh_bands(threshold: u8) -> Vec<(u32, u32)> {
+    let max_distance = 64 - (64 * threshold as u32).div_ceil(100);
+    let count = (max_distance + 1).min(64);
+    (0..count)
+        .map(|band| (band * 64 / count, (band + 1) * 64 / count))
+        .collect()
+}
+
+fn band_value(fingerprint: u64, (start, end): (u32, u32)) -> u64 {
+    (fingerprint >> start) & (u64::MAX >> (64 - (end - start)))
+}
+
+/// Similarity in percent of two files from their SimHash fingerprints.
+/// Files of very different sizes are never considered similar.
+fn similarity_percent(a: u64, b: u64, len_a: usize, len_b: usize) -> u8 {
+    let (small, large) = (len_a.min(len_b), len_a.max(len_b));
+    if large == 0 || small * 100 / large < 50 {
+        return 0;
+    }
+    let matching_bits = 64 - (a ^ b).count_ones();
+    (matching_bits * 100 / 64) as u8
+}
+
+/// 64-bit SimHash over shingles of consecutive words
+pub fn simhash(content: &str) -> u64 {
+    let words: Vec<&str> = content
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut weights = [0_i64; 64];
+    for shingle in words.windows(SHINGLE_WORDS.min(words.len().max(1))) {
+        let mut hasher = DefaultHasher::new();
+        shingle.hash(&mut hasher);
+        let hash = hasher.finish();
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash & (1 << bit) != 0 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, &weight)| weight > 0)
+        .fold(0, |fingerprint, (bit, _)| fingerprint | (1 << bit))
+}
@@ -0,0 +1,1115 @@
+use flate2::read::ZlibDecoder;
+use regex::bytes::Regex;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Most bytes decompressed from one PDF stream or archive entry, so that
+/// compression bombs can't exhaust memory
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Document formats text can be extracted from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Pdf,
+    Docx,
+    Odt,
+}
+
+impl DocumentFormat {
+    /// Detect the format from a file path's extension
+    pub fn from_path(path: &str) -> Option<DocumentFormat> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_lowercase())?;
+        match extension.as_str() {
+            "pdf" => Some(DocumentFormat::Pdf),
+            "docx" => Some(DocumentFormat::Docx),
+            "odt" => Some(DocumentFormat::Odt),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DocumentFormat::Pdf => "PDF",
+            DocumentFormat::Docx => "DOCX",
+            DocumentFormat::Odt => "ODT",
+        }
+    }
+}
+
+/// Extract the text of a PDF, DOCX or ODT document, opened by a line
+/// naming the source format. Text beyond `max_size` bytes is cut off with
+/// a note. Returns `None` if the format is not supported or the document
+/// can't be read (for example an encrypted PDF).
+pub fn extract_text(path: &str, content: &[u8], max_size: usize) -> Option<String> {
+    let format = DocumentFormat::from_path(path)?;
+    let (text, pages) = match format {
+        DocumentFormat::Pdf => {
+            let pages = pdf_pages(content)?;
+            let count = pages.len();
+            (pages.join("\n\n"), Some(count))
+        }
+        DocumentFormat::Docx => (docx_text(&zip_entry(content, "word/document.xml")?), None),
+        DocumentFormat::Odt => (odt_text(&zip_entry(content, "content.xml")?), None),
+    };
+    let text = tidy(&text);
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut output = match pages {
+        Some(1) => format!(
+            "[Text extracted from {} document, 1 page]\n\n",
+            format.name()
+        ),
+        Some(pages) => format!(
+            "[Text extracted from {} document, {} pages]\n\n",
+            format.name(),
+            pages
+        ),
+        None => format!("[Text extracted from {} document]\n\n", format.name()),
+    };
+    if text.len() > max_size {
+        let mut end = max_size;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        output.push_str(&text[..end]);
+        output.push_str(&format!(
+            "\n\n[Truncated: {} more bytes of extracted text]\n",
+            text.len() - end
+        ));
+    } else {
+        output.push_str(&text);
+        output.push('\n');
+    }
+    Some(output)
+}
+
+/// Trim trailing whitespace and collapse runs of blank lines
+fn tidy(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut blank = true;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            if !blank {
+                output.push('\n');
+            }
+            blank = true;
+        } else {
+            output.push_str(line);
+            output.push('\n');
+            blank = false;
+        }
+    }
+    output.trim_end().to_string()
+}
+
+fn zip_entry(content: &[u8], name: &str) -> Option<String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(content)).ok()?;
+    let entry = archive.by_name(name).ok()?;
+    let mut xml = String::new();
+    entry
+        .take(MAX_DECOMPRESSED_SIZE)
+        .read_to_string(&mut xml)
+        .ok()?;
+    Some(xml)
+}
+
+// ---------------------------------------------------------------------------
+// DOCX and ODT
+
+/// A piece of XML: a start tag (with its attributes and whether it closes
+/// itself), an end tag, or text between tags
+enum XmlEvent<'a> {
+    Start(&'a str, &'a str, bool),
+    End(&'a str),
+    Text(&'a str),
+}
+
+/// Split XML into tags and text, skipping comments, processing instructions
+/// and declarations
+fn xml_events(xml: &str) -> impl Iterator<Item = XmlEvent<'_>> {
+    let mut rest = xml;
+    std::iter::from_fn(move || loop {
+        if rest.is_empty() {
+            return None;
+        }
+        let Some(body) = rest.strip_prefix('<') else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            rest = &rest[end..];
+            return Some(XmlEvent::Text(text));
+        };
+        let end = rest.find('>')?;
+        let tag = &body[..end - 1];
+        rest = &rest[end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            return Some(XmlEvent::End(name.trim()));
+        }
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        return Some(XmlEvent::Start(name, attributes, self_closing));
+    })
+}
+
+/// Value of an XML attribute
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{}=\"", name))? + name.len() + 2;
+    let length = attributes[start..].find('"')?;
+    Some(&attributes[start..start + length])
+}
+
+/// Replace XML entities and character references
+fn unescape_xml(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                output.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Paragraphs of a DOCX `word/document.xml`, with headings marked `#`
+fn docx_text(xml: &str) -> String {
+    let mut output = String::new();
+    let mut paragraph = String::new();
+    let mut heading: Option<usize> = None;
+    let mut in_text = false;
+    for event in xml_events(xml) {
+        match event {
+            XmlEvent::Start("w:p", _, false) => {
+                paragraph.clear();
+                heading = None;
+            }
+            XmlEvent::Start("w:pStyle", attributes, _) => {
+                let style = attribute(attributes, "w:val").unwrap_or_default();
+                heading = match style.strip_prefix("Heading") {
+                    Some(level) => level.parse().ok(),
+                    None if style == "Title" => Some(1),
+                    None => heading,
+                };
+            }
+            XmlEvent::Start("w:t", _, false) => in_text = true,
+            XmlEvent::End("w:t") => in_text = false,
+            XmlEvent::Start("w:tab", _, _) => paragraph.push('\t'),
+            XmlEvent::Start("w:br" | "w:cr", _, _) => paragraph.push('\n'),
+            XmlEvent::Text(text) if in_text => paragraph.push_str(&unescape_xml(text)),
+            XmlEvent::End("w:p") => {
+                if let Some(level) = heading.filter(|_| !paragraph.trim().is_empty()) {
+                    output.push_str(&"#".repeat(level.clamp(1, 6)));
+                    output.push(' ');
+                }
+                output.push_str(&paragraph);
+                output.push('\n');
+                paragraph.clear();
+            }
+            _ => {}
+        }
+    }
+    output
+}
+
+/// Paragraphs of an ODT `content.xml`, with headings marked `#`
+fn odt_text(xml: &str) -> String {
+    let mut output = String::new();
+    // Paragraphs nest (notes inside paragraphs), so track the depth
+    let mut depth = 0;
+    for event in xml_events(xml) {
+        match event {
+            XmlEvent::Start("text:p" | "text:h", attributes, self_closing) => {
+                if self_closing {
+                    output.push('\n');
+                    continue;
+                }
+                if depth == 0 {
+                    if let Some(level) = attribute(attributes, "text:outline-level") {
+                        let level: usize = level.parse().unwrap_or(1);
+                        output.push_str(&"#".repeat(level.clamp(1, 6)));
+                        output.push(' ');
+                    }
+                }
+                depth += 1;
+            }
+            XmlEvent::End("text:p" | "text:h") => {
+                depth -= 1;
+                output.push('\n');
+            }
+            XmlEvent::Start("text:s", attributes, _) if depth > 0 => {
+                let count = attribute(attributes, "text:c")
+                    .and_then(|c| c.parse().ok())
+                    .unwrap_or(1);
+                output.push_str(&" ".repeat(count));
+            }
+            XmlEvent::Start("text:tab", _, _) if depth > 0 => output.push('\t'),
+            XmlEvent::Start("text:line-break", _, _) if depth > 0 => output.push('\n'),
+            XmlEvent::Text(text) if depth > 0 => output.push_str(&unescape_xml(text)),
+            _ => {}
+        }
+    }
+    output
+}
+
+// ---------------------------------------------------------------------------
+// PDF
+
+/// A PDF object. Operators in content streams and CMaps are keywords.
+#[derive(Debug, Clone, PartialEq)]
+enum Object {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Name(String),
+    String(Vec<u8>),
+    Array(Vec<Object>),
+    Dict(HashMap<String, Object>),
+    Stream(HashMap<String, Object>, Vec<u8>),
+    Ref(u32),
+    Keyword(String),
+}
+
+impl Object {
+    fn dict(&self) -> Option<&HashMap<String, Object>> {
+        match self {
+            Object::Dict(dict) | Object::Stream(dict, _) => Some(dict),
+            _ => None,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Object> {
+        self.dict()?.get(key)
+    }
+
+    fn name(&self) -> Option<&str> {
+        match self {
+            Object::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        match self {
+            Object::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// Reads PDF objects from bytes
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// Arrays and dictionaries currently open
+    depth: usize,
+}
+
+/// Deepest nesting of arrays and dictionaries the lexer follows. Real
+/// files stay far below it; hostile ones would overflow the stack.
+const MAX_NESTING: usize = 64;
+
+fn is_delimiter(b: u8) -> bool {
+    matches!(
+        b,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+impl<'a> Lexer<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Lexer {
+            data,
+            pos,
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            if b.is_ascii_whitespace() || b == 0 {
+                self.pos += 1;
+            } else if b == b'%' {
+                while self.peek().is_some_and(|b| b != b'\n' && b != b'\r') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// A run of regular (non-delimiter, non-whitespace) characters
+    fn word(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|b| !b.is_ascii_whitespace() && b != 0 && !is_delimiter(b))
+        {
+            self.pos += 1;
+        }
+        &self.data[start..self.pos]
+    }
+
+    fn next_object(&mut self) -> Option<Object> {
+        self.skip_whitespace();
+        let b = self.peek()?;
+        match b {
+            b'/' => {
+                self.pos += 1;
+                Some(Object::Name(decode_name(self.word())))
+            }
+            b'(' => Some(Object::String(self.literal_string())),
+            b'<' if self.data.get(self.pos + 1) == Some(&b'<') => self.nested(Self::dict),
+            b'<' => Some(Object::String(self.hex_string())),
+            b'[' => self.nested(Self::array),
+            b'>' | b')' | b']' | b'{' | b'}' => {
+                self.pos += 1;
+                Some(Object::Keyword((b as char).to_string()))
+            }
+            _ => {
+                let word = self.word();
+                if word.is_empty() {
+                    self.pos += 1;
+                    return Some(Object::Keyword(String::new()));
+                }
+                let text = String::from_utf8_lossy(word);
+                match text.as_ref() {
+                    "true" => return Some(Object::Bool(true)),
+                    "false" => return Some(Object::Bool(false)),
+                    "null" => return Some(Object::Null),
+                    _ => {}
+                }
+                let Ok(number) = text.parse::<f64>() else {
+                    return Some(Object::Keyword(text.into_owned()));
+                };
+                // "12 0 R" is a reference
+                if word.iter().all(u8::is_ascii_digit) {
+                    let saved = self.pos;
+                    self.skip_whitespace();
+                    let generation = self.word();
+                    if !generation.is_empty() && generation.iter().all(u8::is_ascii_digit) {
+                        self.skip_whitespace();
+                        if self.peek() == Some(b'R')
+                            && self
+                                .data
+                                .get(self.pos + 1)
+                                .is_none_or(|b| b.is_ascii_whitespace() || is_delimiter(*b))
+                        {
+                            self.pos += 1;
+                            return Some(Object::Ref(number as u32));
+                        }
+                    }
+                    self.pos = saved;
+                }
+                Some(Object::Number(number))
+            }
+        }
+    }
+
+    /// Read an array or dictionary, giving up past `MAX_NESTING` levels
+    fn nested(&mut self, read: fn(&mut Self) -> Option<Object>) -> Option<Object> {
+        if self.depth >= MAX_NESTING {
+            return None;
+        }
+        self.depth += 1;
+        let object = read(self);
+        self.depth -= 1;
+        object
+    }
+
+    fn dict(&mut self) -> Option<Object> {
+        self.pos += 2;
+        let mut dict = HashMap::new();
+        loop {
+            self.skip_whitespace();
+            if self.data[self.pos..].starts_with(b">>") {
+                self.pos += 2;
+                break;
+            }
+            match self.next_object()? {
+                Object::Name(key) => {
+                    let value = self.next_object()?;
+                    dict.insert(key, value);
+                }
+                // Tolerate junk instead of failing the whole file
+                _ => continue,
+            }
+        }
+        Some(Object::Dict(dict))
+    }
+
+    fn array(&mut self) -> Option<Object> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                b']' => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => items.push(self.next_object()?),
+            }
+        }
+        Some(Object::Array(items))
+    }
+
+    fn literal_string(&mut self) -> Vec<u8> {
+        self.pos += 1;
+        let mut output = Vec::new();
+        let mut depth = 1;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'(' => {
+                    depth += 1;
+                    output.push(b);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                    output.push(b);
+                }
+                b'\\' => {
+                    let Some(escaped) = self.peek() else {
+                        break;
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => output.push(b'\n'),
+                        b'r' => output.push(b'\r'),
+                        b't' => output.push(b'\t'),
+                        b'b' => output.push(8),
+                        b'f' => output.push(12),
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        b'0'..=b'7' => {
+                            let mut value = (escaped - b'0') as u32;
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        value = value * 8 + (d - b'0') as u32;
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            output.push(value as u8);
+                        }
+                        other => output.push(other),
+                    }
+                }
+                _ => output.push(b),
+            }
+        }
+        output
+    }
+
+    fn hex_string(&mut self) -> Vec<u8> {
+        self.pos += 1;
+        let mut digits = Vec::new();
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            if b == b'>' {
+                break;
+            }
+            if let Some(digit) = (b as char).to_digit(16) {
+                digits.push(digit as u8);
+            }
+        }
+        if digits.len() % 2 == 1 {
+            digits.push(0);
+        }
+        digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect()
+    }
+}
+
+/// Decode `#xx` escapes in a name
+fn decode_name(raw: &[u8]) -> String {
+    let mut output = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] == b'#' {
+            if let Some(byte) = raw
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                output.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        output.push(raw[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+/// The objects of a PDF file by number, including those packed in object
+/// streams
+struct PdfDocument {
+    objects: HashMap<u32, Object>,
+}
+
+impl PdfDocument {
+    fn parse(data: &[u8]) -> Option<PdfDocument> {
+        static OBJECT: OnceLock<Regex> = OnceLock::new();
+        let object_start =
+            OBJECT.get_or_init(|| Regex::new(r"(\d+)\s+\d+\s+obj\b").expect("valid regex"));
+
+        let mut objects = HashMap::new();
+        for captures in object_start.captures_iter(data) {
+            let Some(number) = std::str::from_utf8(&captures[1])
+                .ok()
+                .and_then(|n| n.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            let mut lexer = Lexer::new(data, captures.get(0)?.end());
+            let Some(object) = lexer.next_object() else {
+                continue;
+            };
+            let object = match object {
+                Object::Dict(dict) => {
+                    lexer.skip_whitespace();
+                    if data[lexer.pos..].starts_with(b"stream") {
+                        let Some(bytes) = stream_bytes(data, lexer.pos + b"stream".len(), &dict)
+                        else {
+                            continue;
+                        };
+                        Object::Stream(dict, bytes)
+                    } else {
+                        Object::Dict(dict)
+                    }
+                }
+                object => object,
+            };
+            // Later definitions (incremental updates) replace earlier ones
+            objects.insert(number, object);
+        }
+        if objects.is_empty() {
+            return None;
+        }
+
+        let mut document = PdfDocument { objects };
+        document.unpack_object_streams();
+        Some(document)
+    }
+
+    /// Add the objects stored in compressed object streams
+    fn unpack_object_streams(&mut self) {
+        let streams: Vec<Object> = self
+            .objects
+            .values()
+            .filter(|object| object.get("Type").and_then(Object::name) == Some("ObjStm"))
+            .cloned()
+            .collect();
+        for stream in streams {
+            let Some(data) = self.decode_stream(&stream) else {
+                continue;
+            };
+            let count = stream.get("N").and_then(Object::number).unwrap_or(0.0) as usize;
+            let first = stream.get("First").and_then(Object::number).unwrap_or(0.0) as usize;
+            let mut header = Lexer::new(&data, 0);
+            let mut entries = Vec::new();
+            for _ in 0..count {
+                let (Some(Object::Number(number)), Some(Object::Number(offset))) =
+                    (header.next_object(), header.next_object())
+                else {
+                    break;
+                };
+                entries.push((number as u32, offset as usize));
+            }
+            for (number, offset) in entries {
+                let Some(start) = first.checked_add(offset) else {
+                    continue;
+                };
+                if let Some(object) = Lexer::new(&data, start).next_object() {
+                    self.objects.entry(number).or_insert(object);
+                }
+            }
+        }
+    }
+
+    fn resolve<'b>(&'b self, object: &'b Object) -> &'b Object {
+        let mut object = object;
+        // References can chain, but not forever
+        for _ in 0..8 {
+            match object {
+                Object::Ref(number) => match self.objects.get(number) {
+                    Some(target) => object = target,
+                    None => return &Object::Null,
+                },
+                _ => return object,
+            }
+        }
+        object
+    }
+
+    fn get<'b>(&'b self, object: &'b Object, key: &str) -> Option<&'b Object> {
+        let value = self.resolve(object).get(key)?;
+        Some(self.resolve(value))
+    }
+
+    /// Decoded stream data. Only FlateDecode is supported, which covers
+    /// content streams, CMaps and object streams in practice.
+    fn decode_stream(&self, stream: &Object) -> Option<Vec<u8>> {
+        let Object::Stream(_, data) = self.resolve(stream) else {
+            return None;
+        };
+        let filters: Vec<&str> = match self.get(stream, "Filter") {
+            None | Some(Object::Null) => Vec::new(),
+            Some(Object::Name(name)) => vec![name.as_str()],
+            Some(Object::Array(names)) => names.iter().filter_map(Object::name).collect(),
+            Some(_) => return None,
+        };
+        let mut data = data.clone();
+        for filter in filters {
+            match filter {
+                "FlateDecode" | "Fl" => {
+                    let mut decoded = Vec::new();
+                    // Truncated streams still yield the data before the damage
+                    let _ = ZlibDecoder::new(data.as_slice())
+                        .take(MAX_DECOMPRESSED_SIZE)
+                        .read_to_end(&mut decoded);
+                    if decoded.is_empty() {
+                        return None;
+                    }
+                    data = decoded;
+                }
+                _ => return None,
+            }
+        }
+        Some(data)
+    }
+
+    /// The pages in document order with the resources they inherit,
+    /// found by walking the page tree
+    fn pages(&self) -> Vec<(&Object, Option<&Object>)> {
+        let catalog = self
+            .objects
+            .values()
+            .find(|object| object.get("Type").and_then(Object::name) == Some("Catalog"));
+        let mut pages = Vec::new();
+        if let Some(root) = catalog.and_then(|catalog| catalog.get("Pages")) {
+            self.collect_pages(root, None, &mut pages, 0);
+        }
+        if pages.is_empty() {
+            // No usable page tree: fall back to page objects by number
+            let mut numbers: Vec<&u32> = self
+                .objects
+                .iter()
+                .filter(|(_, object)| object.get("Type").and_then(Object::name) == Some("Page"))
+                .map(|(number, _)| number)
+                .collect();
+            numbers.sort();
+            pages = numbers
+                .into_iter()
+                .map(|number| (&self.objects[number], None))
+                .collect();
+        }
+        pages
+    }
+
+    fn collect_pages<'b>(
+        &'b self,
+        node: &'b Object,
+        inherited: Option<&'b Object>,
+        pages: &mut Vec<(&'b Object, Option<&'b Object>)>,
+        depth: usize,
+    ) {
+        if depth > 32 {
+            return;
+        }
+        let node = self.resolve(node);
+        let resources = self.get(node, "Resources").or(inherited);
+        match self.get(node, "Kids") {
+            Some(Object::Array(kids)) => {
+                for kid in kids {
+                    self.collect_pages(kid, resources, pages, depth + 1);
+                }
+            }
+            _ => pages.push((node, resources)),
+        }
+    }
+
+    /// The text of each page
+    fn page_texts(&self) -> Vec<String> {
+        self.pages()
+            .into_iter()
+            .map(|(page, inherited)| {
+                let resources = self.get(page, "Resources").or(inherited);
+                let fonts = self.page_fonts(resources);
+                let contents: Vec<&Object> = match self.get(page, "Contents") {
+                    Some(Object::Array(streams)) => streams.iter().collect(),
+                    Some(stream @ Object::Stream(..)) => vec![stream],
+                    _ => Vec::new(),
+                };
+                let mut content = Vec::new();
+                for stream in contents {
+                    // The same stream may be listed over and over
+                    if content.len() as u64 >= MAX_DECOMPRESSED_SIZE {
+                        break;
+                    }
+                    if let Some(data) = self.decode_stream(stream) {
+                        content.extend_from_slice(&data);
+                        content.push(b'\n');
+                    }
+                }
+                page_text(&content, &fonts)
+            })
+            .collect()
+    }
+
+    /// The fonts of a page's resources, by resource name
+    fn page_fonts(&self, resources: Option<&Object>) -> HashMap<String, Font> {
+        let mut fonts = HashMap::new();
+        let Some(font_dict) = resources
+            .and_then(|resources| self.get(resources, "Font"))
+            .and_then(Object::dict)
+        else {
+            return fonts;
+        };
+        for (name, font) in font_dict {
+            let two_byte = self.get(font, "Subtype").and_then(Object::name) == Some("Type0");
+            let to_unicode = self
+                .get(font, "ToUnicode")
+                .and_then(|cmap| self.decode_stream(cmap))
+                .map(|cmap| parse_cmap(&cmap))
+                .unwrap_or_default();
+            fonts.insert(
+                name.clone(),
+                Font {
+                    two_byte,
+                    to_unicode,
+                },
+            );
+        }
+        fonts
+    }
+}
+
+/// The raw bytes of a stream starting after the `stream` keyword, or
+/// `None` if its `/Length` is out of any file's range
+fn stream_bytes(data: &[u8], mut start: usize, dict: &HashMap<String, Object>) -> Option<Vec<u8>> {
+    if data.get(start) == Some(&b'\r') {
+        start += 1;
+    }
+    if data.get(start) == Some(&b'\n') {
+        start += 1;
+    }
+    let declared = match dict.get("Length").and_then(Object::number) {
+        Some(length) => Some(start.checked_add(length as usize)?),
+        None => None,
+    }
+    .filter(|&end| {
+        data[end.min(data.len())..]
+            .trim_ascii_start()
+            .starts_with(b"endstream")
+    });
+    let end = declared.unwrap_or_else(|| {
+        // Indirect or wrong /Length: look for the end marker instead
+        data[start..]
+            .windows(b"endstream".len())
+            .position(|window| window == b"endstream")
+            .map(|offset| start + offset)
+            .unwrap_or(data.len())
+    });
+    Some(data[start..end.min(data.len())].to_vec())
+}
+
+/// Text of a PDF: one string per page. Returns `None` for encrypted or
+/// unreadable files and for documents without any text.
+fn pdf_pages(data: &[u8]) -> Option<Vec<String>> {
+    if !data.starts_with(b"%PDF") {
+        return None;
+    }
+    let document = PdfDocument::parse(data)?;
+    let encrypted = document
+        .objects
+        .values()
+        .any(|object| object.get("Encrypt").is_some());
+    if encrypted {
+        return None;
+    }
+    let pages = document.page_texts();
+    if pages.iter().all(|page| page.trim().is_empty()) {
+        return None;
+    }
+    Some(pages)
+}
+
+/// How to turn a font's character codes into text
+#[derive(Debug, Default)]
+struct Font {
+    /// Whether codes are two bytes (composite fonts) rather than one
+    two_byte: bool,
+    to_unicode: HashMap<u32, String>,
+}
+
+impl Font {
+    fn decode(&self, bytes: &[u8], output: &mut String) {
+        if self.two_byte {
+            for pair in bytes.chunks(2) {
+                let code = pair.iter().fold(0u32, |code, &b| code << 8 | b as u32);
+                if let Some(text) = self.to_unicode.get(&code) {
+                    output.push_str(text);
+                }
+            }
+        } else {
+            for &b in bytes {
+                match self.to_unicode.get(&(b as u32)) {
+                    Some(text) => output.push_str(text),
+                    // Standard encodings agree with Latin-1 on the common characters
+                    None => output.push(b as char),
+                }
+            }
+        }
+    }
+}
+
+/// Parse the `bfchar` and `bfrange` mappings of a ToUnicode CMap
+fn parse_cmap(data: &[u8]) -> HashMap<u32, String> {
+    let code = |bytes: &[u8]| bytes.iter().fold(0u32, |code, &b| code << 8 | b as u32);
+    let mut map = HashMap::new();
+    let mut lexer = Lexer::new(data, 0);
+    let mut operands: Vec<Object> = Vec::new();
+    while let Some(object) = lexer.next_object() {
+        match object {
+            Object::Keyword(keyword) if keyword == "endbfchar" => {
+                for pair in operands.chunks(2) {
+                    if let [Object::String(source), Object::String(target)] = pair {
+                        map.insert(code(source), utf16_text(target));
+                    }
+                }
+                operands.clear();
+            }
+            Object::Keyword(keyword) if keyword == "endbfrange" => {
+                for range in operands.chunks(3) {
+                    let [Object::String(low), Object::String(high), target] = range else {
+                        continue;
+                    };
+                    let (low, high) = (code(low), code(high));
+                    // Skip absurd ranges in broken files
+                    if high < low || high - low > 0xFFFF {
+                        continue;
+                    }
+                    match target {
+                        Object::String(start) => {
+                            let mut units = utf16_units(start);
+                            for code in low..=high {
+                                map.insert(code, String::from_utf16_lossy(&units));
+                                if let Some(last) = units.last_mut() {
+                                    *last = last.wrapping_add(1);
+                                }
+                            }
+                        }
+                        Object::Array(targets) => {
+                            for (code, target) in (low..=high).zip(targets) {
+                                if let Object::String(target) = target {
+                                    map.insert(code, utf16_text(target));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                operands.clear();
+            }
+            Object::Keyword(_) => operands.clear(),
+            object => operands.push(object),
+        }
+    }
+    map
+}
+
+fn utf16_units(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks(2)
+        .map(|pair| pair.iter().fold(0u16, |unit, &b| unit << 8 | b as u16))
+        .collect()
+}
+
+fn utf16_text(bytes: &[u8]) -> String {
+    String::from_utf16_lossy(&utf16_units(bytes))
+}
+
+/// Position of the text cursor, tracked to tell where lines break
+#[derive(Debug, Default)]
+struct TextState {
+    /// Vertical position of the current line
+    y: f64,
+    /// Vertical scale of the text matrix, applied to relative moves
+    scale: f64,
+    /// Line spacing used by `T*`
+    leading: f64,
+    /// Whether the cursor moved since text was last shown
+    moved: bool,
+    /// Vertical position of the text shown last
+    shown_y: Option<f64>,
+}
+
+impl TextState {
+    fn move_by(&mut self, dy: f64) {
+        self.y += dy * self.scale;
+        self.moved = true;
+    }
+
+    /// Separate the text about to be shown from the previous text: a line
+    /// break if the line changed, a space if the cursor moved along it
+    fn separate(&mut self, output: &mut String) {
+        if let Some(shown_y) = self.shown_y {
+            if (shown_y - self.y).abs() > 2.0 {
+                if !output.ends_with('\n') {
+                    output.push('\n');
+                }
+            } else if self.moved && !output.ends_with([' ', '\n']) {
+                output.push(' ');
+            }
+        }
+        self.shown_y = Some(self.y);
+        self.moved = false;
+    }
+}
+
+/// Interpret the text operators of a page's content stream. Line breaks
+/// follow vertical moves of the text cursor; horizontal moves and large
+/// gaps inside `TJ` become spaces.
+fn page_text(content: &[u8], fonts: &HashMap<String, Font>) -> String {
+    let default_font = Font::default();
+    let mut font = &default_font;
+    let mut output = String::new();
+    let mut operands: Vec<Object> = Vec::new();
+    let mut lexer = Lexer::new(content, 0);
+    let mut state = TextState {
+        scale: 1.0,
+        ..TextState::default()
+    };
+
+    while let Some(object) = lexer.next_object() {
+        let Object::Keyword(operator) = object else {
+            operands.push(object);
+            continue;
+        };
+        let numbers: Vec<f64> = operands.iter().filter_map(Object::number).collect();
+        match operator.as_str() {
+            "BT" => {
+                state.y = 0.0;
+                state.scale = 1.0;
+                state.moved = true;
+            }
+            "Tf" => {
+                if let Some(Object::Name(name)) = operands.first() {
+                    font = fonts.get(name).unwrap_or(&default_font);
+                }
+            }
+            "TL" => state.leading = numbers.last().copied().unwrap_or(0.0),
+            "Td" | "TD" => {
+                if let [.., _, dy] = numbers.as_slice() {
+                    if operator == "TD" {
+                        state.leading = -dy;
+                    }
+                    state.move_by(*dy);
+                }
+            }
+            "Tm" => {
+                if let [.., _, d, _, f] = numbers.as_slice() {
+                    state.scale = if *d == 0.0 { 1.0 } else { d.abs() };
+                    state.y = *f;
+                    state.moved = true;
+                }
+            }
+            "T*" => state.move_by(-state.leading),
+            "Tj" | "'" | "\"" => {
+                if operator != "Tj" {
+                    state.move_by(-state.leading);
+                }
+                if let Some(Object::String(bytes)) = operands.last() {
+                    state.separate(&mut output);
+                    font.decode(bytes, &mut output);
+                }
+            }
+            "TJ" => {
+                if let Some(Object::Array(items)) = operands.last() {
+                    state.separate(&mut output);
+                    for item in items {
+                        match item {
+                            Object::String(bytes) => font.decode(bytes, &mut output),
+                            Object::Number(gap) if *gap < -200.0 && !output.ends_with(' ') => {
+                                output.push(' ')
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            "ID" => {
+                // Inline image data runs until "EI"
+                let data = &content[lexer.pos..];
+                let end = data
+                    .windows(3)
+                    .position(|w| w[0].is_ascii_whitespace() && &w[1..] == b"EI")
+                    .map(|offset| offset + 3)
+                    .unwrap_or(data.len());
+                lexer.pos += end;
+            }
+            _ => {}
+        }
+        operands.clear();
+    }
+    output
+}
@@ -0,0 +1,460 @@
+use crate::models::ProcessedFile;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+use tracing::debug;
+
+/// Summary of a single commit, used in headers and per-file history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommitSummary {
+    /// Abbreviated commit SHA
+    pub sha: String,
+    /// Author name
+    pub author: String,
+    /// Commit date (YYYY-MM-DD, UTC)
+    pub date: String,
+    /// First line of the commit message
+    pub subject: String,
+}
+
+impl CommitSummary {
+    pub fn from_commit(commit: &git2::Commit) -> Self {
+        Self {
+            sha: short_sha(commit.id()),
+            author: commit.author().name().unwrap_or("unknown").to_string(),
+            date: format_git_date(commit.time().seconds()),
+            subject: commit.summary().unwrap_or("").to_string(),
+        }
+    }
+}
+
+/// A working-tree change as reported by `git status`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusEntry {
+    /// Short status code (`M`, `A`, `D`, `R` or `??`)
+    pub code: String,
+    /// Path relative to the repository root
+    pub path: String,
+}
+
+/// Repository-level metadata rendered as the git header
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitMetadata {
+    /// Name of the repository (the working directory name)
+    pub repository: String,
+    /// Current branch, or `None` for a detached HEAD
+    pub branch: Option<String>,
+    /// Full SHA of HEAD
+    pub head: String,
+    /// Subject line of the HEAD commit
+    pub head_subject: String,
+    /// Whether the working tree has uncommitted changes
+    pub dirty: bool,
+    /// Uncommitted changes (empty when clean)
+    pub status: Vec<StatusEntry>,
+    /// Most recent commits, newest first
+    pub recent_commits: Vec<CommitSummary>,
+}
+
+impl GitMetadata {
+    /// Render the metadata as a plain-text header placed before the tree header
+    pub fn render(&self) -> String {
+        let mut output = String::from("Git repository:\n");
+        output.push_str(&format!("  Name: {}\n", self.repository));
+        output.push_str(&format!(
+            "  Branch: {}\n",
+            self.branch.as_deref().unwrap_or("(detached HEAD)")
+        ));
+        output.push_str(&format!("  HEAD: {} {}\n", self.head, self.head_subject));
+        if self.dirty {
+            output.push_str(&format!(
+                "  Worktree: dirty ({} changed)\n",
+                self.status.len()
+            ));
+            for entry in &self.status {
+                output.push_str(&format!("    {:>2} {}\n", entry.code, entry.path));
+            }
+        } else {
+            output.push_str("  Worktree: clean\n");
+        }
+        if !self.recent_commits.is_empty() {
+            output.push_str("  Recent commits:\n");
+            for commit in &self.recent_commits {
+                output.push_str(&format!("    {} {}\n", commit.sha, commit.subject));
+            }
+        }
+        output.push('\n'); // Add blank line after header
+
+        output
+    }
+}
+
+/// Open the git repository containing `path`, searching parent directories.
+pub fn open_repository(path: &Path) -> Option<git2::Repository> {
+    match git2::Repository::discover(path) {
+        Ok(repo) => Some(repo),
+        Err(_) => {
+            debug!("Not a Git repository or unable to open: {:?}", path);
+            None
+        }
+    }
+}
+
+/// Collect branch, HEAD, worktree status and the last `log_count` commits
+/// for the repository containing `path`.
+/// Returns None if the path is not inside a git repository or HEAD is unborn.
+pub fn collect_git_metadata(path: &Path, log_count: usize) -> Option<GitMetadata> {
+    let repo = open_repository(path)?;
+    let head = repo.head().ok()?;
+    let head_commit = head.peel_to_commit().ok()?;
+
+    let branch = if head.is_branch() {
+        head.shorthand().map(|s| s.to_string())
+    } else {
+        None
+    };
+
+    let repository = repo
+        .workdir()
+        .unwrap_or_else(|| repo.path())
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_default();
+
+    let status = collect_status(&repo);
+
+    let mut recent_commits = Vec::new();
+    if log_count > 0 {
+        if let Ok(mut revwalk) = repo.revwalk() {
+            if revwalk.push(head_commit.id()).is_ok() {
+                revwalk
+                    .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+                    .ok()?;
+                for oid in revwalk.take(log_count).flatten() {
+                    if let Ok(commit) = repo.find_commit(oid) {
+                        recent_commits.push(CommitSummary::from_commit(&commit));
+                    }
+                }
+            }
+        }
+    }
+
+    Some(GitMetadata {
+        repository,
+        branch,
+        head: head_commit.id().to_string(),
+        head_subject: head_commit.summary().unwrap_or("").to_string(),
+        dirty: !status.is_empty(),
+        status,
+        recent_commits,
+    })
+}
+
+/// List uncommitted changes (including untracked files), sorted by path
+pub fn collect_status(repo: &git2::Repository) -> Vec<StatusEntry> {
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .renames_head_to_index(true);
+
+    let statuses = match repo.statuses(Some(&mut options)) {
+        Ok(statuses) => statuses,
+        Err(e) => {
+            debug!("Unable to read git status: {:?}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut entries: Vec<StatusEntry> = statuses
+        .iter()
+        .filter_map(|entry| {
+            let code = status_code(entry.status())?;
+            let path = entry
+                .head_to_index()
+                .and_then(|d| d.new_file().path().map(|p| p.to_path_buf()))
+                .or_else(|| entry.path().map(std::path::PathBuf::from))?;
+            Some(StatusEntry {
+                code: code.to_string(),
+                path: path.to_string_lossy().replace('\\', "/"),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    entries
+}
+
+/// Map a git2 status to a short, porcelain-like code
+pub fn status_code(status: git2::Status) -> Option<&'static str> {
+    if status.is_conflicted() {
+        Some("U")
+    } else if status.is_index_renamed() || status.is_wt_renamed() {
+        Some("R")
+    } else if status.is_index_new() {
+        Some("A")
+    } else if status.is_wt_new() {
+        Some("??")
+    } else if status.is_index_deleted() || status.is_wt_deleted() {
+        Some("D")
+    } else if status.is_index_modified()
+        || status.is_wt_modified()
+        || status.is_index_typechange()
+        || status.is_wt_typechange()
+    {
+        Some("M")
+    } else {
+        None
+    }
+}
+
+/// Abbreviate a commit id to 7 hex characters
+pub fn short_sha(oid: git2::Oid) -> String {
+    let full = oid.to_string();
+    full[..7.min(full.len())].to_string()
+}
+
+/// Format a git timestamp (seconds since epoch) as a UTC date
+pub fn format_git_date(seconds: i64) -> String {
+    time::OffsetDateTime::from_unix_timestamp(seconds)
+        .map(|dt| {
+            format!(
+                "{:04}-{:02}-{:02}",
+                dt.year(),
+                u8::from(dt.month()),
+                dt.day()
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Path of `base_dir` relative to the repository working directory, with a
+/// trailing slash (empty when `base_dir` is the repository root).
+pub fn repo_relative_prefix(repo: &git2::Repository, base_dir: &Path) -> Option<String> {
+    let workdir = repo.workdir()?.canonicalize().ok()?;
+    let base = base_dir.canonicalize().ok()?;
+    let relative = base.strip_prefix(&workdir).ok()?;
+    let prefix = relative.to_string_lossy().replace('\\', "/");
+    if prefix.is_empty() {
+        Some(prefix)
+    } else {
+        Some(format!("{}/", prefix))
+    }
+}
+
+/// Collect the last `per_file` commits touching each of `paths` (relative to
+/// the repository root). Uses a single revwalk over at most `max_commits`
+/// commits, diffing each commit against its first parent, and stops early
+/// once every path has enough history.
+pub fn collect_file_history(
+    repo: &git2::Repository,
+    paths: &HashSet<String>,
+    per_file: usize,
+    max_commits: usize,
+) -> HashMap<String, Vec<CommitSummary>> {
+    let mut history: HashMap<String, Vec<CommitSummary>> = HashMap::new();
+    if paths.is_empty() || per_file == 0 {
+        return history;
+    }
+
+    let mut revwalk = match repo.revwalk() {
+        Ok(revwalk) => revwalk,
+        Err(e) => {
+            debug!("Unable to get revwalk: {:?}", e);
+            return history;
+        }
+    };
+    if revwalk.push_head().is_err()
+        || revwalk
+            .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+            .is_err()
+    {
+        return history;
+    }
+
+    let mut complete = 0;
+    for oid in revwalk.take(max_commits).flatten() {
+        let commit = match repo.find_commit(oid) {
+            Ok(commit) => commit,
+            Err(_) => continue,
+        };
+        let tree = match commit.tree() {
+            Ok(tree) => tree,
+            Err(_) => continue,
+        };
+        let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+        let diff = match repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) {
+            Ok(diff) => diff,
+            Err(_) => continue,
+        };
+
+        let mut summary = None;
+        for delta in diff.deltas() {
+            let path = match delta.new_file().path().or_else(|| delta.old_file().path()) {
+                Some(path) => path.to_string_lossy().replace('\\', "/"),
+                None => continue,
+            };
+            if !paths.contains(&path) {
+                continue;
+            }
+            let entries = history.entry(path).or_default();
+            if entries.len() < per_file {
+                let summary = summary.get_or_insert_with(|| CommitSummary::from_commit(&commit));
+                entries.push(summary.clone());
+                if entries.len() == per_file {
+                    complete += 1;
+                }
+            }
+        }
+
+        if complete == paths.len() {
+            break;
+        }
+    }
+
+    history
+}
+
+/// Attach per-file commit history to `files`, whose paths are relative to `base_dir`.
+pub fn attach_file_history(
+    files: &mut [ProcessedFile],
+    base_dir: &Path,
+    per_file: usize,
+    max_commits: usize,
+) {
+    // A bare file name has an empty parent directory
+    let base_dir = if base_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        base_dir
+    };
+    let repo = match open_repository(base_dir) {
+        Some(repo) => repo,
+        None => return,
+    };
+    let prefix = match repo_relative_prefix(&repo, base_dir) {
+        Some(prefix) => prefix,
+        None => return,
+    };
+
+    let paths: HashSet<String> = files
+        .iter()
+        .map(|f| format!("{}{}", prefix, f.rel_path))
+        .collect();
+    let mut history = collect_file_history(&repo, &paths, per_file, max_commits);
+
+    for file in files.iter_mut() {
+        if let Some(entries) = history.remove(&format!("{}{}", prefix, file.rel_path)) {
+            file.history = entries;
+        }
+    }
+}
+
+/// Render commit history as a compact comment block placed above file content
+pub fn render_history_block(history: &[CommitSummary], (start, end): (&str, &str)) -> String {
+    let line = |text: String| {
+        // Keep commit text from closing a block comment early
+        let text = if end.is_empty() {
+            text
+        } else {
+            text.replace("*/", "* /").replace("--", "- -")
+        };
+        format!("{}{}{}\n", start, text, end)
+    };
+    let mut block = line(" History:".to_string());
+    for commit in history {
+        block.push_str(&line(format!(
+            "   {} {} {}: {}",
+            commit.sha, commit.date, commit.author, commit.subject
+        )));
+    }
+    block
+}
+
+/// Working-tree status for files under `base_dir`, with paths relative to `base_dir`.
+/// Returns an empty list outside a git repository.
+pub fn collect_status_under(base_dir: &Path) -> Vec<StatusEntry> {
+    // A bare file name has an empty parent directory
+    let base_dir = if base_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        base_dir
+    };
+    let repo = match open_repository(base_dir) {
+        Some(repo) => repo,
+        None => return Vec::new(),
+    };
+    let prefix = match repo_relative_prefix(&repo, base_dir) {
+        Some(prefix) => prefix,
+        None => return Vec::new(),
+    };
+
+    collect_status(&repo)
+        .into_iter()
+        .filter_map(|entry| {
+            let path = entry.path.strip_prefix(&prefix)?.to_string();
+            Some(StatusEntry { path, ..entry })
+        })
+        .collect()
+}
+
+/// Attach git status codes to `files`, whose paths are relative to `base_dir`.
+pub fn attach_git_status(files: &mut [ProcessedFile], base_dir: &Path) {
+    let status: HashMap<String, String> = collect_status_under(base_dir)
+        .into_iter()
+        .map(|entry| (entry.path, entry.code))
+        .collect();
+    if status.is_empty() {
+        return;
+    }
+
+    for file in files.iter_mut() {
+        if let Some(code) = status.get(&file.rel_path) {
+            file.git_status = Some(code.clone());
+        }
+    }
+}
+
+/// Restrict `files` (paths relative to `base_dir`) to those touched by commits
+/// whose message matches `pattern`, or with `boost` set, keep every file and add
+/// `boost` to the priority of the touched ones. Files are left unchanged
+/// outside a git repository.
+pub fn select_by_commits(
+    files: &mut Vec<ProcessedFile>,
+    base_dir: &Path,
+    pattern: &regex::Regex,
+    since: Option<i64>,
+    boost: Option<i32>,
+) {
+    // A bare file name has an empty parent directory
+    let base_dir = if base_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        base_dir
+    };
+    let Some(repo) = open_repository(base_dir) else {
+        return;
+    };
+    let Some(prefix) = repo_relative_prefix(&repo, base_dir) else {
+        return;
+    };
+    let Some(touched) =
+        crate::priority::get_files_touched_by_matching_commits(&repo, pattern, since)
+    else {
+        return;
+    };
+
+    let is_touched =
+        |file: &ProcessedFile| touched.contains(&format!("{}{}", prefix, file.rel_path));
+    match boost {
+        Some(boost) => {
+            for file in files.iter_mut().filter(|f| is_touched(f)) {
+                file.priority += boost;
+            }
+        }
+        None => files.retain(is_touched),
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    config::YekConfig,
+    count_tokens,
+    git::{self, CommitSummary},
+    models::ProcessedFile,
+    parse_token_limit,
+};
+use anyhow::{anyhow, Result};
+use bytesize::ByteSize;
+use std::{path::Path, str::FromStr};
+use tracing::debug;
+
+/// A distinct version of a file as recorded in git
+#[derive(Debug, Clone)]
+pub struct FileVersion {
+    /// Commit that introduced this version
+    pub commit: CommitSummary,
+    /// File content at that commit
+    pub content: String,
+}
+
+/// Collect up to `versions` distinct versions of `path` reachable from HEAD,
+/// oldest first. Consecutive commits that leave the file unchanged are
+/// collapsed into the commit that introduced the content.
+/// Returns the path relative to the repository root along with the versions.
+pub fn collect_file_versions(path: &Path, versions: usize) -> Result<(String, Vec<FileVersion>)> {
+    // The file may no longer exist in the working tree, so resolve its parent
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("history: Invalid path '{}'", path.display()))?;
+    let repo = git::open_repository(parent)
+        .ok_or_else(|| anyhow!("history: '{}' is not in a git repository", path.display()))?;
+    let prefix = git::repo_relative_prefix(&repo, parent)
+        .ok_or_else(|| anyhow!("history: '{}' is outside the repository", path.display()))?;
+    let repo_path = format!("{}{}", prefix, file_name);
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+
+    // Walk newest to oldest; a version is attributed to the oldest commit in
+    // the run of commits that share its blob
+    let mut newest_first: Vec<(git2::Oid, git2::Oid)> = Vec::new();
+    for oid in revwalk.flatten() {
+        let commit = match repo.find_commit(oid) {
+            Ok(commit) => commit,
+            Err(e) => {
+                debug!("Failed to find commit for OID {:?}: {:?}", oid, e);
+                continue;
+            }
+        };
+        let blob_id = commit
+            .tree()
+            .ok()
+            .and_then(|tree| tree.get_path(Path::new(&repo_path)).ok())
+            .map(|entry| entry.id());
+        let Some(blob_id) = blob_id else {
+            // The file does not exist at this commit, so its history starts here
+            if !newest_first.is_empty() {
+                break;
+            }
+            continue;
+        };
+
+        match newest_first.last_mut() {
+            Some((last_blob, last_commit)) if *last_blob == blob_id => *last_commit = oid,
+            _ => {
+                if newest_first.len() == versions {
+                    break;
+                }
+                newest_first.push((blob_id, oid));
+            }
+        }
+    }
+
+    if newest_first.is_empty() {
+        return Err(anyhow!("history: No committed versions of '{}'", repo_path));
+    }
+
+    let mut result = Vec::with_capacity(newest_first.len());
+    for (blob_id, commit_id) in newest_first.into_iter().rev() {
+        let blob = repo.find_blob(blob_id)?;
+        let commit = repo.find_commit(commit_id)?;
+        result.push(FileVersion {
+            commit: CommitSummary::from_commit(&commit),
+            content: String::from_utf8_lossy(blob.content()).to_string(),
+        });
+    }
+    Ok((repo_path, result))
+}
+
+/// Unified diff between two versions of `path`
+pub fn unified_diff(path: &str, old: &str, new: &str) -> Result<String> {
+    let mut patch = git2::Patch::from_buffers(
+        old.as_bytes(),
+        Some(Path::new(path)),
+        new.as_bytes(),
+        Some(Path::new(path)),
+        None,
+    )?;
+    let buf = patch.to_buf()?;
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+/// Serialize the version history of a single file: the oldest version in full
+/// and each later version as a unified diff. The oldest versions are dropped
+/// until the output fits the size or token budget (the newest is always kept).
+pub fn serialize_history(config: &YekConfig) -> Result<(String, Vec<ProcessedFile>)> {
+    let path = config
+        .history
+        .as_deref()
+        .ok_or_else(|| anyhow!("history: A file path is required"))?;
+    let (repo_path, versions) = collect_file_versions(Path::new(path), config.versions.max(1))?;
+
+    let cap = if config.token_mode {
+        parse_token_limit(&config.tokens)?
+    } else {
+        ByteSize::from_str(&config.max_size)
+            .map_err(|e| anyhow!("max_size: Invalid size format: {}", e))?
+            .as_u64() as usize
+    };
+
+    let mut start = 0;
+    let output = loop {
+        let output = render_versions(&repo_path, &versions[start..], config)?;
+        let size = if config.token_mode {
+            count_tokens(&output)
+        } else {
+            output.len()
+        };
+        if size <= cap || start + 1 == versions.len() {
+            break output;
+        }
+        debug!(
+            "History of {} exceeds budget, dropping version {}",
+            repo_path, versions[start].commit.sha
+        );
+        start += 1;
+    };
+
+    let shown = &versions[start..];
+    let newest = shown.last().expect("at least one version");
+    let mut file = ProcessedFile::new(repo_path, newest.content.clone(), 0, 0);
+    file.history = shown.iter().rev().map(|v| v.commit.clone()).collect();
+    Ok((output, vec![file]))
+}
+
+/// Render versions (oldest first) as text or JSON
+fn render_versions(path: &str, versions: &[FileVersion], config: &YekConfig) -> Result<String> {
+    if config.json {
+        let mut entries = Vec::with_capacity(versions.len());
+        for (i, version) in versions.iter().enumerate() {
+            let mut entry = serde_json::json!({
+                "filename": path,
+                "commit": version.commit,
+            });
+            if i == 0 {
+                entry["content"] = serde_json::json!(version.content);
+            } else {
+                entry["diff"] = serde_json::json!(unified_diff(
+                    path,
+                    &versions[i - 1].content,
+                    &version.content
+                )?);
+            }
+            entries.push(entry);
+        }
+        return Ok(serde_json::to_string_pretty(&entries)?);
+    }
+
+    let template = config
+        .output_template
+        .as_ref()
+        .expect("output_template should be set");
+    let mut blocks = Vec::with_capacity(versions.len());
+    for (i, version) in versions.iter().enumerate() {
+        let commit = &version.commit;
+        let (label, content) = if i == 0 {
+            (
+                format!(
+                    "{} @ {} {} {}: {}",
+                    path, commit.sha, commit.date, commit.author, commit.subject
+                ),
+                version.content.clone(),
+            )
+        } else {
+            (
+                format!(
+                    "{} @ {} {} {}: {} (diff from {})",
+                    path,
+                    commit.sha,
+                    commit.date,
+                    commit.author,
+                    commit.subject,
+                    versions[i - 1].commit.sha
+                ),
+                unified_diff(path, &versions[i - 1].content, &version.content)?,
+            )
+        };
+        blocks.push(
+            template
+                .replace("FILE_PATH", &label)
+                .replace("FILE_STATUS", "")
+                .replace("FILE_CONTENT", &content)
+                // Handle both literal "\n" and escaped "\\n"
+                .replace("\\\\\n", "\n") // First handle escaped newline
+                .replace("\\\\n", "\n"), // Then handle escaped \n sequence
+        );
+    }
+    Ok(blocks.join("\n"))
+}
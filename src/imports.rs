@@ -0,0 +1,498 @@
+use crate::{lang::Language, models::ProcessedFile};
+use path_slash::PathBufExt;
+use regex::Regex;
+use std::{
+    collections::{HashMap, VecDeque},
+    path::Path,
+    sync::OnceLock,
+};
+use tracing::debug;
+
+/// Priority boost for seed files; it decays linearly with graph distance
+pub const FOLLOW_IMPORTS_BOOST_MAX: i32 = 1000;
+
+/// Extensions tried, in order, when resolving extensionless JS/TS imports
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"];
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid import regex"))
+}
+
+/// Restrict `files` to the seeds and the files they transitively import, up
+/// to `depth` hops, and (with `reverse`) the files that transitively import
+/// the seeds. Priority is boosted by graph distance: seeds get
+/// `FOLLOW_IMPORTS_BOOST_MAX` and the boost shrinks evenly with each hop.
+/// `seeds` are paths relative to the same base as `files`. If none of them is
+/// among `files`, the files are left as they are and `false` is returned.
+pub fn follow_imports(
+    files: &mut Vec<ProcessedFile>,
+    seeds: &[String],
+    depth: usize,
+    reverse: bool,
+) -> bool {
+    let known: HashMap<&str, &str> = files
+        .iter()
+        .map(|f| (f.rel_path.as_str(), f.content.as_str()))
+        .collect();
+    let seeds: Vec<&str> = seeds
+        .iter()
+        .map(|s| s.as_str())
+        .filter(|s| known.contains_key(s))
+        .collect();
+    if seeds.is_empty() {
+        return false;
+    }
+
+    let graph = build_import_graph(&known);
+    let mut distances = bfs(&graph, &seeds, depth);
+    if reverse {
+        let mut reversed: HashMap<String, Vec<String>> = HashMap::new();
+        for (from, targets) in &graph {
+            for to in targets {
+                reversed.entry(to.clone()).or_default().push(from.clone());
+            }
+        }
+        for (path, distance) in bfs(&reversed, &seeds, depth) {
+            let entry = distances.entry(path).or_insert(distance);
+            *entry = (*entry).min(distance);
+        }
+    }
+
+    files.retain(|f| distances.contains_key(&f.rel_path));
+    for file in files.iter_mut() {
+        let distance = distances[&file.rel_path];
+        let boost = FOLLOW_IMPORTS_BOOST_MAX * (depth + 1 - distance) as i32 / (depth + 1) as i32;
+        debug!(
+            "Import graph: {} at distance {} (+{})",
+            file.rel_path, distance, boost
+        );
+        file.priority += boost;
+    }
+    true
+}
+
+/// Breadth-first distances from `seeds`, at most `depth` hops
+fn bfs(
+    graph: &HashMap<String, Vec<String>>,
+    seeds: &[&str],
+    depth: usize,
+) -> HashMap<String, usize> {
+    let mut distances: HashMap<String, usize> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for seed in seeds {
+        distances.insert(seed.to_string(), 0);
+        queue.push_back(seed.to_string());
+    }
+    while let Some(path) = queue.pop_front() {
+        let distance = distances[&path];
+        if distance == depth {
+            continue;
+        }
+        for next in graph.get(&path).into_iter().flatten() {
+            if !distances.contains_key(next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back(next.clone());
+            }
+        }
+    }
+    distances
+}
+
+/// Map each file to the files it imports, resolved against `known`
+/// (relative path → content)
+pub fn build_import_graph(known: &HashMap<&str, &str>) -> HashMap<String, Vec<String>> {
+    let go_modules = go_modules(known);
+    known
+        .iter()
+        .map(|(path, content)| {
+            let mut targets: Vec<String> = match Language::from_path(path) {
+                Some(Language::Rust) => rust_imports(path, content, known),
+                Some(Language::JavaScript) | Some(Language::TypeScript) => {
+                    js_imports(path, content, known)
+                }
+                Some(Language::Python) => python_imports(path, content, known),
+                Some(Language::Go) => go_imports(content, known, &go_modules),
+                _ => Vec::new(),
+            };
+            targets.retain(|target| target != path);
+            targets.sort();
+            targets.dedup();
+            (path.to_string(), targets)
+        })
+        .collect()
+}
+
+/// Join `dir` and a relative `spec`, resolving `.` and `..` textually
+fn join_path(dir: &str, spec: &str) -> Option<String> {
+    let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    for part in spec.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            _ => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rfind('/').map(|i| &path[..i]).unwrap_or("")
+}
+
+fn first_known(candidates: &[String], known: &HashMap<&str, &str>) -> Option<String> {
+    candidates
+        .iter()
+        .find(|c| known.contains_key(c.as_str()))
+        .cloned()
+}
+
+/// Directory holding a Rust file's child modules
+fn rust_module_dir(path: &str) -> String {
+    let stem = Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    let dir = parent_dir(path);
+    if matches!(stem, "lib" | "main" | "mod") {
+        dir.to_string()
+    } else {
+        join_path(dir, stem).unwrap_or_default()
+    }
+}
+
+/// Crate root file (`lib.rs` or `main.rs`) in `dir`, preferring `path` itself
+fn rust_crate_root_file(dir: &str, path: &str, known: &HashMap<&str, &str>) -> Option<String> {
+    let candidates = ["lib.rs", "main.rs"].map(|name| join_path(dir, name).unwrap_or_default());
+    if candidates.iter().any(|c| c == path) {
+        return Some(path.to_string());
+    }
+    first_known(&candidates, known)
+}
+
+/// Directory of the crate root (`lib.rs` or `main.rs`) a Rust file belongs to
+fn rust_crate_root(path: &str, known: &HashMap<&str, &str>) -> String {
+    let mut dir = parent_dir(path);
+    loop {
+        let root = |name: &str| join_path(dir, name).unwrap_or_default();
+        if known.contains_key(root("lib.rs").as_str())
+            || known.contains_key(root("main.rs").as_str())
+        {
+            return dir.to_string();
+        }
+        if dir.is_empty() {
+            return parent_dir(path).to_string();
+        }
+        dir = parent_dir(dir);
+    }
+}
+
+/// Resolve `mod foo;` and `use crate::`/`super::`/`self::` paths
+fn rust_imports(path: &str, content: &str, known: &HashMap<&str, &str>) -> Vec<String> {
+    static MOD_RE: OnceLock<Regex> = OnceLock::new();
+    static USE_RE: OnceLock<Regex> = OnceLock::new();
+    static ALIAS_RE: OnceLock<Regex> = OnceLock::new();
+    let mod_re = regex(
+        &MOD_RE,
+        r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_][A-Za-z0-9_]*)\s*;",
+    );
+    let use_re = regex(&USE_RE, r"\buse\s+((?:crate|super|self)::[^;]+);");
+    let alias_re = regex(&ALIAS_RE, r"\s+as\s+[A-Za-z_][A-Za-z0-9_]*");
+
+    let module_dir = rust_module_dir(path);
+    let mut targets = Vec::new();
+
+    for captures in mod_re.captures_iter(content) {
+        let name = &captures[1];
+        let candidates = [
+            format!("{}/{}.rs", module_dir, name),
+            format!("{}/{}/mod.rs", module_dir, name),
+        ]
+        .map(|c| c.trim_start_matches('/').to_string());
+        targets.extend(first_known(&candidates, known));
+    }
+
+    for captures in use_re.captures_iter(content) {
+        let tree: String = alias_re
+            .replace_all(&captures[1], "")
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        for segments in expand_use_tree(&tree) {
+            let mut segments = segments.iter().map(|s| s.as_str()).peekable();
+            let crate_root = rust_crate_root(path, known);
+            let mut base = match segments.next() {
+                Some("crate") => crate_root.clone(),
+                Some("self") => module_dir.clone(),
+                Some("super") => parent_dir(&module_dir).to_string(),
+                _ => continue,
+            };
+            while segments.peek() == Some(&"super") {
+                segments.next();
+                base = parent_dir(&base).to_string();
+            }
+            let rest: Vec<&str> = segments.filter(|s| *s != "*" && *s != "self").collect();
+            // Try the longest module path first: crate::a::b::Item → a/b.rs, then a.rs
+            let module_file = (1..=rest.len()).rev().find_map(|len| {
+                let module = rest[..len].join("/");
+                let candidates = [
+                    format!("{}/{}.rs", base, module),
+                    format!("{}/{}/mod.rs", base, module),
+                ]
+                .map(|c| c.trim_start_matches('/').to_string());
+                first_known(&candidates, known)
+            });
+            // Items defined in the crate root itself, like `use crate::Config`
+            let found = module_file.or_else(|| {
+                (base == crate_root)
+                    .then(|| rust_crate_root_file(&base, path, known))
+                    .flatten()
+            });
+            targets.extend(found);
+        }
+    }
+    targets
+}
+
+/// Expand a whitespace-free use tree like `crate::a::{b::C,d}` into paths
+fn expand_use_tree(tree: &str) -> Vec<Vec<String>> {
+    let Some(open) = tree.find('{') else {
+        return vec![tree
+            .split("::")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect()];
+    };
+    let prefix: Vec<String> = tree[..open]
+        .split("::")
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    let inner = tree[open + 1..]
+        .strip_suffix('}')
+        .unwrap_or(&tree[open + 1..]);
+
+    // Split the braced list on top-level commas
+    let mut items = Vec::new();
+    let mut level = 0;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '{' => level += 1,
+            '}' => level -= 1,
+            ',' if level == 0 => {
+                items.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&inner[start..]);
+
+    items
+        .into_iter()
+        .filter(|item| !item.is_empty())
+        .flat_map(|item| {
+            expand_use_tree(item).into_iter().map(|rest| {
+                let mut full = prefix.clone();
+                full.extend(rest);
+                full
+            })
+        })
+        .collect()
+}
+
+/// Resolve relative JS/TS `import`, `export ... from`, `require()` and `import()`
+fn js_imports(path: &str, content: &str, known: &HashMap<&str, &str>) -> Vec<String> {
+    static IMPORT_RE: OnceLock<Regex> = OnceLock::new();
+    let import_re = regex(
+        &IMPORT_RE,
+        r#"(?:(?:import|export)\s[^'";]*?from\s*|import\s*|(?:require|import)\s*\(\s*)['"](\.[^'"]*)['"]"#,
+    );
+
+    let dir = parent_dir(path);
+    import_re
+        .captures_iter(content)
+        .filter_map(|captures| {
+            let base = join_path(dir, &captures[1])?;
+            let mut candidates = vec![base.clone()];
+            // TypeScript sources are commonly imported with a .js extension
+            if let Some(stem) = base.strip_suffix(".js") {
+                candidates.extend(["ts", "tsx"].iter().map(|ext| format!("{}.{}", stem, ext)));
+            }
+            candidates.extend(JS_EXTENSIONS.iter().map(|ext| format!("{}.{}", base, ext)));
+            candidates.extend(JS_EXTENSIONS.iter().map(|ext| {
+                format!("{}/index.{}", base, ext)
+                    .trim_start_matches('/')
+                    .to_string()
+            }));
+            first_known(&candidates, known)
+        })
+        .collect()
+}
+
+/// Resolve Python `import a.b` and `from a.b import c` (including relative imports)
+fn python_imports(path: &str, content: &str, known: &HashMap<&str, &str>) -> Vec<String> {
+    static IMPORT_RE: OnceLock<Regex> = OnceLock::new();
+    static FROM_RE: OnceLock<Regex> = OnceLock::new();
+    let import_re = regex(&IMPORT_RE, r"(?m)^\s*import\s+([^#\n]+)");
+    let from_re = regex(
+        &FROM_RE,
+        r"(?m)^\s*from\s+(\.*)([A-Za-z0-9_.]*)\s+import\s+\(?([^#\n)]+)",
+    );
+
+    let dir = parent_dir(path);
+    // Absolute imports may be rooted at any ancestor directory of the file
+    let mut roots = vec![dir.to_string()];
+    let mut ancestor = dir;
+    while !ancestor.is_empty() {
+        ancestor = parent_dir(ancestor);
+        roots.push(ancestor.to_string());
+    }
+
+    let resolve = |base: &str, module: &str| -> Option<String> {
+        let module_path = module.replace('.', "/");
+        let joined = join_path(base, &module_path)?;
+        let candidates = [
+            format!("{}.py", joined),
+            format!("{}/__init__.py", joined)
+                .trim_start_matches('/')
+                .to_string(),
+        ];
+        first_known(&candidates, known)
+    };
+    let names = |list: &str| -> Vec<String> {
+        list.split(',')
+            .filter_map(|item| item.split_whitespace().next())
+            .map(|name| name.to_string())
+            .collect()
+    };
+
+    let mut targets = Vec::new();
+    for captures in import_re.captures_iter(content) {
+        for module in names(&captures[1]) {
+            targets.extend(roots.iter().find_map(|root| resolve(root, &module)));
+        }
+    }
+    for captures in from_re.captures_iter(content) {
+        let dots = captures[1].len();
+        let module = &captures[2];
+        let bases: Vec<String> = if dots > 0 {
+            let mut base = dir.to_string();
+            for _ in 1..dots {
+                base = parent_dir(&base).to_string();
+            }
+            vec![base]
+        } else {
+            roots.clone()
+        };
+        for base in &bases {
+            let mut found = Vec::new();
+            // `from pkg import module` imports submodules as well as names
+            for name in names(&captures[3]) {
+                let submodule = if module.is_empty() {
+                    name
+                } else {
+                    format!("{}.{}", module, name)
+                };
+                found.extend(resolve(base, &submodule));
+            }
+            if !module.is_empty() {
+                found.extend(resolve(base, module));
+            }
+            if !found.is_empty() {
+                targets.extend(found);
+                break;
+            }
+        }
+    }
+    targets
+}
+
+/// Module path → directory of each `go.mod` among the known files
+fn go_modules(known: &HashMap<&str, &str>) -> Vec<(String, String)> {
+    static MODULE_RE: OnceLock<Regex> = OnceLock::new();
+    let module_re = regex(&MODULE_RE, r"(?m)^\s*module\s+(\S+)");
+    known
+        .iter()
+        .filter(|(path, _)| **path == "go.mod" || path.ends_with("/go.mod"))
+        .filter_map(|(path, content)| {
+            let captures = module_re.captures(content)?;
+            Some((captures[1].to_string(), parent_dir(path).to_string()))
+        })
+        .collect()
+}
+
+/// Resolve Go imports of packages in the same module to the package's files
+fn go_imports(
+    content: &str,
+    known: &HashMap<&str, &str>,
+    modules: &[(String, String)],
+) -> Vec<String> {
+    static BLOCK_RE: OnceLock<Regex> = OnceLock::new();
+    static SINGLE_RE: OnceLock<Regex> = OnceLock::new();
+    static QUOTED_RE: OnceLock<Regex> = OnceLock::new();
+    let block_re = regex(&BLOCK_RE, r"(?s)\bimport\s*\(([^)]*)\)");
+    let single_re = regex(
+        &SINGLE_RE,
+        r#"(?m)^\s*import\s+(?:[A-Za-z_.]+\s+)?"([^"]+)""#,
+    );
+    let quoted_re = regex(&QUOTED_RE, r#""([^"]+)""#);
+
+    let mut import_paths: Vec<&str> = single_re
+        .captures_iter(content)
+        .filter_map(|c| c.get(1).map(|m| m.as_str()))
+        .collect();
+    for block in block_re.captures_iter(content) {
+        let block = block.get(1).map(|m| m.as_str()).unwrap_or("");
+        import_paths.extend(
+            quoted_re
+                .captures_iter(block)
+                .filter_map(|c| c.get(1).map(|m| m.as_str())),
+        );
+    }
+
+    let mut targets = Vec::new();
+    for import_path in import_paths {
+        for (module, module_dir) in modules {
+            let Some(package) = import_path
+                .strip_prefix(module.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            else {
+                continue;
+            };
+            let Some(package_dir) = join_path(module_dir, package) else {
+                continue;
+            };
+            targets.extend(
+                known
+                    .keys()
+                    .filter(|p| {
+                        parent_dir(p) == package_dir
+                            && p.ends_with(".go")
+                            && !p.ends_with("_test.go")
+                    })
+                    .map(|p| p.to_string()),
+            );
+        }
+    }
+    targets
+}
+
+/// Resolve a seed path given on the command line to a path relative to `base_dir`
+pub fn seed_rel_path(seed: &str, base_dir: &Path) -> String {
+    let base_dir = if base_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        base_dir
+    };
+    let relative = Path::new(seed).canonicalize().ok().and_then(|seed| {
+        let base = base_dir.canonicalize().ok()?;
+        Some(seed.strip_prefix(base).ok()?.to_path_buf())
+    });
+    match relative {
+        Some(relative) => relative.to_slash().unwrap_or_default().to_string(),
+        None => seed.replace('\\', "/").trim_start_matches("./").to_string(),
+    }
+}
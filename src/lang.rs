@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Programming languages yek knows how to annotate and parse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    Rust,
+    Go,
+    Python,
+    JavaScript,
+    TypeScript,
+    C,
+    Cpp,
+    CSharp,
+    Java,
+    Kotlin,
+    Scala,
+    Swift,
+    Dart,
+    Php,
+    Ruby,
+    Shell,
+    Lua,
+    Sql,
+    Haskell,
+    Css,
+    Html,
+}
+
+impl Language {
+    /// Detect the language from a file path's extension
+    pub fn from_path(path: &str) -> Option<Language> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_lowercase())?;
+
+        let language = match extension.as_str() {
+            "rs" => Language::Rust,
+            "go" => Language::Go,
+            "py" | "pyi" => Language::Python,
+            "js" | "jsx" | "mjs" | "cjs" => Language::JavaScript,
+            "ts" | "tsx" | "mts" | "cts" => Language::TypeScript,
+            "c" | "h" => Language::C,
+            "cpp" | "cc" | "cxx" | "c++" | "hpp" | "hxx" | "h++" => Language::Cpp,
+            "cs" => Language::CSharp,
+            "java" => Language::Java,
+            "kt" | "kts" => Language::Kotlin,
+            "scala" => Language::Scala,
+            "swift" => Language::Swift,
+            "dart" => Language::Dart,
+            "php" => Language::Php,
+            "rb" => Language::Ruby,
+            "sh" | "bash" | "zsh" | "fish" => Language::Shell,
+            "lua" => Language::Lua,
+            "sql" => Language::Sql,
+            "hs" | "lhs" => Language::Haskell,
+            "css" | "scss" | "sass" | "less" => Language::Css,
+            "html" | "htm" | "vue" | "svelte" => Language::Html,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Get the language name as a string for display purposes
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Go => "go",
+            Language::Python => "python",
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::CSharp => "csharp",
+            Language::Java => "java",
+            Language::Kotlin => "kotlin",
+            Language::Scala => "scala",
+            Language::Swift => "swift",
+            Language::Dart => "dart",
+            Language::Php => "php",
+            Language::Ruby => "ruby",
+            Language::Shell => "shell",
+            Language::Lua => "lua",
+            Language::Sql => "sql",
+            Language::Haskell => "haskell",
+            Language::Css => "css",
+            Language::Html => "html",
+        }
+    }
+
+    /// Line comment marker, if the language has one
+    pub fn line_comment(self) -> Option<&'static str> {
+        match self {
+            Language::Python | Language::Ruby | Language::Shell => Some("#"),
+            Language::Lua | Language::Sql | Language::Haskell => Some("--"),
+            Language::Css | Language::Html => None,
+            _ => Some("//"),
+        }
+    }
+}
+
+/// Start and end of an annotation comment line placed above a file's
+/// content; the end is empty for line comments. CSS gets `/* */`, HTML,
+/// XML and Markdown get `<!-- -->`, and other files without a known
+/// comment syntax fall back to `#`.
+pub fn annotation_comment(path: &str) -> (&'static str, &'static str) {
+    match Language::from_path(path) {
+        Some(Language::Css) => ("/*", " */"),
+        Some(Language::Html) => ("<!--", " -->"),
+        Some(language) => (language.line_comment().unwrap_or("#"), ""),
+        None => {
+            let extension = Path::new(path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.to_lowercase())
+                .unwrap_or_default();
+            match extension.as_str() {
+                "xml" | "svg" | "xhtml" | "xsl" | "xslt" | "plist" | "md" | "markdown" => {
+                    ("<!--", " -->")
+                }
+                _ => ("#", ""),
+            }
+        }
+    }
+}
@@ -4,31 +4,57 @@ use bytesize::ByteSize;
 use content_inspector::{inspect, ContentType};
 use rayon::prelude::*;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::File,
     io::{self, Read},
     path::Path,
     str::FromStr,
-    sync::OnceLock,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
 };
 use tiktoken_rs::CoreBPE;
 
+pub mod binary;
+pub mod blame;
 pub mod category;
+pub mod chunks;
 pub mod config;
+pub mod data;
+pub mod dedupe;
 pub mod defaults;
+pub mod documents;
 pub mod error;
+pub mod git;
+pub mod history;
+pub mod imports;
+pub mod lang;
+pub mod license;
+pub mod lockfile;
+pub mod minify;
 pub mod models;
+pub mod notebook;
+pub mod outline;
 pub mod parallel;
 pub mod pipeline;
 pub mod priority;
+pub mod relevance;
+pub mod repo_map;
 pub mod repository;
+pub mod selection;
+pub mod snippets;
+pub mod split;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod symbols;
 pub mod tree;
 
 use config::YekConfig;
 use models::ProcessedFile;
 use parallel::process_files_parallel;
 use priority::compute_recentness_boost;
-use tree::generate_tree;
+use tree::generate_tree_with_markers;
 
 // Add a static BPE encoder for reuse
 static TOKENIZER: OnceLock<CoreBPE> = OnceLock::new();
@@ -61,11 +87,16 @@ pub fn is_text_file(path: &Path, user_binary_extensions: &[String]) -> io::Resul
 
 /// Main entrypoint for serialization, used by CLI and tests
 pub fn serialize_repo(config: &YekConfig) -> Result<(String, Vec<ProcessedFile>)> {
+    // History mode serializes a single file's versions instead of the inputs
+    if config.history.is_some() {
+        return history::serialize_history(config);
+    }
+
     // Validate input paths and warn about non-existent ones
     let mut non_existent_paths = Vec::new();
 
     for path_str in &config.input_paths {
-        let path = Path::new(path_str);
+        let path = Path::new(selection::parse_input_path(path_str).0);
         // Check if path exists as a file, directory, or could be a glob pattern
         if !path.exists() && !path_str.contains('*') && !path_str.contains('?') {
             non_existent_paths.push(path_str.clone());
@@ -101,13 +132,82 @@ pub fn serialize_repo(config: &YekConfig) -> Result<(String, Vec<ProcessedFile>)
     let recentness_boost =
         compute_recentness_boost(&combined_commit_times, config.git_boost_max.unwrap_or(100));
 
+    // Resolve commit message search once for all input paths
+    let commits_matching = match &config.commits_matching {
+        Some(pattern) => {
+            let regex = regex::Regex::new(pattern)
+                .map_err(|e| anyhow!("commits_matching: Invalid regex '{}': {}", pattern, e))?;
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let since = config
+                .since
+                .as_deref()
+                .map(|since| priority::parse_since(since, now))
+                .transpose()?;
+            Some((regex, since))
+        }
+        None => None,
+    };
+
     // Process files in parallel for each input path
+    let seeds_found = AtomicBool::new(false);
+    let blame_cache = blame::BlameCache::default();
     let merged_files = config
         .input_paths
         .par_iter()
         .map(|path_str| {
+            let (path_str, selection) = selection::parse_input_path(path_str);
             let path = Path::new(path_str);
-            process_files_parallel(path, config, &recentness_boost)
+            let mut files = process_files_parallel(path, config, &recentness_boost)?;
+            if let Some((regex, since)) = &commits_matching {
+                git::select_by_commits(
+                    &mut files,
+                    &parallel::base_dir_for_input(path),
+                    regex,
+                    *since,
+                    config.commits_boost,
+                );
+            }
+            if !config.follow_imports.is_empty() {
+                let base_dir = parallel::base_dir_for_input(path);
+                let seeds: Vec<String> = config
+                    .follow_imports
+                    .iter()
+                    .map(|seed| imports::seed_rel_path(seed, &base_dir))
+                    .collect();
+                if imports::follow_imports(
+                    &mut files,
+                    &seeds,
+                    config.import_depth,
+                    config.reverse_imports,
+                ) {
+                    seeds_found.store(true, Ordering::Relaxed);
+                }
+            }
+            if config.file_history > 0 {
+                git::attach_file_history(
+                    &mut files,
+                    &parallel::base_dir_for_input(path),
+                    config.file_history,
+                    config.max_git_depth.try_into().unwrap_or(0),
+                );
+            }
+            if config.blame {
+                blame::attach_blame(
+                    &mut files,
+                    &parallel::base_dir_for_input(path),
+                    &blame_cache,
+                );
+            }
+            if config.git_status {
+                git::attach_git_status(&mut files, &parallel::base_dir_for_input(path));
+            }
+            if let Some(selection) = &selection {
+                selection::apply_selection(&mut files, selection);
+            }
+            Ok(files)
         })
         .collect::<Result<Vec<Vec<ProcessedFile>>>>()?
         .into_iter()
@@ -116,6 +216,57 @@ pub fn serialize_repo(config: &YekConfig) -> Result<(String, Vec<ProcessedFile>)
 
     let mut files = merged_files;
 
+    // Strip license headers shared by many files before ranking and budgeting
+    if config.strip_license_headers {
+        let stripped = license::strip_license_headers(&mut files);
+        tracing::debug!("Stripped license headers from {} files", stripped);
+    }
+
+    // Rank by relevance to the search query across all input paths
+    if let Some(query) = &config.query {
+        let boosts = priority::compute_relevance_boost(
+            &files,
+            query,
+            config.query_boost_max.unwrap_or(1000),
+        );
+        for (file, boost) in files.iter_mut().zip(boosts) {
+            file.priority += boost;
+        }
+    }
+
+    // Reduce files to the regions matching the grep pattern
+    if let Some(pattern) = &config.grep {
+        let regex = regex::Regex::new(pattern)
+            .map_err(|e| anyhow!("grep: Invalid regex '{}': {}", pattern, e))?;
+        snippets::grep_files(&mut files, &regex, config.context);
+    }
+
+    // Reduce files to their outline, either all of them or the low-priority ones
+    if config.outline || config.outline_below.is_some() {
+        let threshold = if config.outline {
+            None
+        } else {
+            config.outline_below
+        };
+        outline::outline_files(&mut files, threshold);
+    }
+
+    // Minify source files, so budgeting counts the reduced content
+    if let Some(level) = config.minify {
+        minify::minify_files(&mut files, level, config.keep_doc_comments);
+    }
+
+    // Keep one representative of duplicate files, so the budget goes to other files
+    if config.dedupe || config.dedupe_similarity.is_some() {
+        let duplicates = dedupe::dedupe_files(&mut files, config.dedupe_similarity);
+        tracing::debug!("Replaced {} duplicate files with references", duplicates);
+    }
+
+    // Split oversized files into parts that can be budgeted separately
+    if let Some(limit) = config.split_limit()? {
+        files = split::split_large_files(files, limit, config.token_mode);
+    }
+
     // Sort final (priority asc, then file_index asc)
     files.par_sort_by(|a, b| {
         a.priority
@@ -123,6 +274,12 @@ pub fn serialize_repo(config: &YekConfig) -> Result<(String, Vec<ProcessedFile>)
             .then_with(|| a.rel_path.cmp(&b.rel_path))
     });
 
+    if !config.follow_imports.is_empty() && !seeds_found.load(Ordering::Relaxed) {
+        eprintln!(
+            "Warning: None of the --follow-imports seed files were found in the input paths; including all files."
+        );
+    }
+
     // If no files were processed and we had non-existent paths, provide additional context
     if files.is_empty() && !non_existent_paths.is_empty() {
         eprintln!("Warning: No files were processed. All specified paths were non-existent or contained no valid files.");
@@ -140,13 +297,27 @@ pub fn serialize_repo(config: &YekConfig) -> Result<(String, Vec<ProcessedFile>)
 }
 
 pub fn concat_files(files: &[ProcessedFile], config: &YekConfig) -> anyhow::Result<String> {
+    let chunks_jsonl = config.format.as_deref() == Some("chunks-jsonl");
+
+    // Collect git metadata header if requested
+    let git_metadata = if config.git_header && !config.tree_only && !chunks_jsonl {
+        find_git_metadata(config)
+    } else {
+        None
+    };
+
     // Generate tree header if requested
     let tree_header = if config.tree_header || config.tree_only {
         let file_paths: Vec<std::path::PathBuf> = files
             .iter()
             .map(|f| std::path::PathBuf::from(&f.rel_path))
             .collect();
-        generate_tree(&file_paths)
+        let markers = if config.git_status {
+            collect_status_markers(files, config)
+        } else {
+            HashMap::new()
+        };
+        generate_tree_with_markers(&file_paths, &markers)
     } else {
         String::new()
     };
@@ -165,103 +336,282 @@ pub fn concat_files(files: &[ProcessedFile], config: &YekConfig) -> anyhow::Resu
             .as_u64() as usize
     };
 
-    // Account for tree header size in capacity calculations
-    let tree_header_size = if config.tree_header {
+    // Text headers (git metadata, license headers, then tree) are placed before
+    // the file contents. In JSON mode they are emitted as siblings of the files array.
+    let license_headers = license::collect_license_headers(files);
+    let header = if config.json {
+        let mut header = match &git_metadata {
+            Some(metadata) => serde_json::to_string(metadata)?,
+            None => String::new(),
+        };
+        if !license_headers.is_empty() {
+            header.push_str(&serde_json::to_string(&license_headers)?);
+        }
+        header
+    } else {
+        let mut header = git_metadata
+            .as_ref()
+            .map(|metadata| metadata.render())
+            .unwrap_or_default();
+        header.push_str(&license::render_license_headers(&license_headers));
+        if config.tree_header {
+            header.push_str(&tree_header);
+        }
+        header
+    };
+
+    // The repository map is sized against all candidate files; once the
+    // included files are known it is regenerated within that size
+    let measure = |text: &str| {
         if config.token_mode {
-            count_tokens(&tree_header)
+            count_tokens(text)
         } else {
-            tree_header.len()
+            text.len()
         }
+    };
+    let map_budget = if config.repo_map && !config.json {
+        let candidates: Vec<&ProcessedFile> = files.iter().collect();
+        let map = repo_map::generate_repo_map(
+            &candidates,
+            &[],
+            cap * config.repo_map_share / 100,
+            measure,
+        );
+        measure(&map)
     } else {
         0
     };
 
-    accumulated += tree_header_size;
+    // Account for header size in capacity calculations
+    let header_size = measure(&header) + map_budget;
+
+    accumulated += header_size;
 
-    // Sort by priority (asc) and file_index (asc)
+    // Fill the budget with the most important files first (priority desc)
     let mut sorted_files: Vec<_> = files.iter().collect();
     sorted_files.sort_by(|a, b| {
-        a.priority
-            .cmp(&b.priority)
+        b.priority
+            .cmp(&a.priority)
             .then_with(|| a.rel_path.cmp(&b.rel_path))
     });
 
     let mut files_to_include = Vec::new();
+    // Paths in the output. Representatives sort before their duplicates,
+    // so a duplicate is only kept if the file it refers to was.
+    let mut included_paths: HashSet<&str> = HashSet::new();
     for file in sorted_files {
-        let content_size = if config.token_mode {
+        if file
+            .duplicate
+            .as_ref()
+            .is_some_and(|duplicate| !included_paths.contains(duplicate.of.as_str()))
+        {
+            continue;
+        }
+        let content_size = if chunks_jsonl {
+            measure(&file.content)
+        } else if config.token_mode {
             // Format the file content with template first, then count tokens
-            let content = format_content_with_line_numbers(&file.content, config.line_numbers);
             let formatted = if config.json {
-                serde_json::to_string(&serde_json::json!({
-                    "filename": &file.rel_path,
-                    "content": content,
-                }))
-                .map_err(|e| anyhow!("Failed to serialize JSON: {}", e))?
+                serde_json::to_string(&format_json_entry(file, config))
+                    .map_err(|e| anyhow!("Failed to serialize JSON: {}", e))?
             } else {
-                config
-                    .output_template
-                    .as_ref()
-                    .expect("output_template should be set")
-                    .replace("FILE_PATH", &file.rel_path)
-                    .replace("FILE_CONTENT", &content)
-                    // Handle both literal "\n" and escaped "\\n"
-                    .replace("\\\\\n", "\n") // First handle escaped newline
-                    .replace("\\\\n", "\n") // Then handle escaped \n sequence
+                format_file_entry(file, config)
             };
             count_tokens(&formatted)
         } else {
-            let content = format_content_with_line_numbers(&file.content, config.line_numbers);
-            content.len()
+            render_file_content(file, config).len()
         };
 
         if accumulated + content_size <= cap {
             accumulated += content_size;
+            included_paths.insert(&file.rel_path);
             files_to_include.push(file);
-        } else {
-            break;
         }
     }
 
+    // Output in priority order (asc), so the most important files come last
+    files_to_include.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| a.rel_path.cmp(&b.rel_path))
+    });
+
+    if chunks_jsonl {
+        return chunks::chunks_jsonl(&files_to_include, config.chunk_tokens, config.chunk_overlap);
+    }
+
+    let header = if map_budget > 0 {
+        let excluded: Vec<&ProcessedFile> = if config.repo_map_excluded {
+            let included: HashSet<&str> = files_to_include
+                .iter()
+                .map(|f| f.rel_path.as_str())
+                .collect();
+            files
+                .iter()
+                .filter(|f| !included.contains(f.rel_path.as_str()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let map = repo_map::generate_repo_map(&files_to_include, &excluded, map_budget, measure);
+        format!("{}{}", header, map)
+    } else {
+        header
+    };
+
     let main_content = if config.json {
         // JSON array of objects
-        serde_json::to_string_pretty(
-            &files_to_include
-                .iter()
-                .map(|f| {
-                    let content = format_content_with_line_numbers(&f.content, config.line_numbers);
-                    serde_json::json!({
-                        "filename": &f.rel_path,
-                        "content": content,
-                    })
-                })
-                .collect::<Vec<_>>(),
-        )?
+        let entries = files_to_include
+            .iter()
+            .map(|f| format_json_entry(f, config))
+            .collect::<Vec<_>>();
+
+        if git_metadata.is_none() && license_headers.is_empty() {
+            serde_json::to_string_pretty(&entries)?
+        } else {
+            let mut output = serde_json::json!({ "files": entries });
+            if let Some(metadata) = &git_metadata {
+                output["git"] = serde_json::json!(metadata);
+            }
+            if !license_headers.is_empty() {
+                output["license_headers"] = serde_json::json!(license_headers);
+            }
+            serde_json::to_string_pretty(&output)?
+        }
     } else {
         // Use the user-defined template
         files_to_include
             .iter()
-            .map(|f| {
-                let content = format_content_with_line_numbers(&f.content, config.line_numbers);
-                config
-                    .output_template
-                    .as_ref()
-                    .expect("output_template should be set")
-                    .replace("FILE_PATH", &f.rel_path)
-                    .replace("FILE_CONTENT", &content)
-                    // Handle both literal "\n" and escaped "\\n"
-                    .replace("\\\\\n", "\n") // First handle escaped newline
-                    .replace("\\\\n", "\n") // Then handle escaped \n sequence
-            })
+            .map(|f| format_file_entry(f, config))
             .collect::<Vec<_>>()
             .join("\n")
     };
 
-    // Combine tree header with main content
-    if config.tree_header {
-        Ok(format!("{}{}", tree_header, main_content))
-    } else {
+    // Combine headers with main content
+    if config.json {
         Ok(main_content)
+    } else {
+        Ok(format!("{}{}", header, main_content))
+    }
+}
+
+/// Collect git metadata for the first input path that lives inside a git repository
+fn find_git_metadata(config: &YekConfig) -> Option<git::GitMetadata> {
+    let log_count = config.git_log_count;
+    if config.input_paths.is_empty() {
+        return git::collect_git_metadata(Path::new("."), log_count);
+    }
+    config
+        .input_paths
+        .iter()
+        .find_map(|path_str| git::collect_git_metadata(Path::new(path_str), log_count))
+}
+
+/// Collect git status markers for the tree header: the status of every
+/// processed file, plus deleted files under directory inputs
+fn collect_status_markers(files: &[ProcessedFile], config: &YekConfig) -> HashMap<String, String> {
+    let mut markers: HashMap<String, String> = files
+        .iter()
+        .filter_map(|f| Some((f.rel_path.clone(), f.git_status.clone()?)))
+        .collect();
+    for path_str in &config.input_paths {
+        let path = Path::new(selection::parse_input_path(path_str).0);
+        if !path.is_dir() {
+            continue;
+        }
+        for entry in git::collect_status_under(path) {
+            if entry.code == "D" {
+                markers.insert(entry.path, entry.code);
+            }
+        }
+    }
+    markers
+}
+
+/// Render a file's content for text output: per-file annotations (such as
+/// commit history) followed by the formatted content
+fn render_file_content(file: &ProcessedFile, config: &YekConfig) -> String {
+    let content = format_file_content(file, config);
+    if file.history.is_empty() {
+        return content;
+    }
+
+    let mut rendered =
+        git::render_history_block(&file.history, lang::annotation_comment(&file.rel_path));
+    rendered.push_str(&content);
+    rendered
+}
+
+/// Render a single file through the user-defined output template
+fn format_file_entry(file: &ProcessedFile, config: &YekConfig) -> String {
+    let content = render_file_content(file, config);
+    config
+        .output_template
+        .as_ref()
+        .expect("output_template should be set")
+        .replace("FILE_PATH", &selection::display_path(file))
+        .replace("FILE_STATUS", file.git_status.as_deref().unwrap_or(""))
+        .replace("FILE_CONTENT", &content)
+        // Handle both literal "\n" and escaped "\\n"
+        .replace("\\\\\n", "\n") // First handle escaped newline
+        .replace("\\\\n", "\n") // Then handle escaped \n sequence
+}
+
+/// Build the JSON object for a single file
+fn format_json_entry(file: &ProcessedFile, config: &YekConfig) -> serde_json::Value {
+    let content = format_file_content(file, config);
+    let mut entry = serde_json::json!({
+        "filename": &file.rel_path,
+        "content": content,
+    });
+    if config.file_history > 0 {
+        entry["history"] = serde_json::json!(file.history);
+    }
+    if config.git_status {
+        entry["status"] = serde_json::json!(file.git_status);
+    }
+    if let (Some(selection), Some(first), Some(last)) =
+        (&file.selection, file.windows.first(), file.windows.last())
+    {
+        entry["selection"] = serde_json::json!(selection.label());
+        entry["lines"] = serde_json::json!([first.start, last.end]);
+    }
+    if let Some(duplicate) = &file.duplicate {
+        entry["duplicate_of"] = serde_json::json!(duplicate.of);
+        entry["similarity"] = serde_json::json!(duplicate.similarity);
+    }
+    if let (Some(part), Some(first), Some(last)) =
+        (&file.part, file.windows.first(), file.windows.last())
+    {
+        entry["part"] = serde_json::json!([part.index, part.count]);
+        entry["lines"] = serde_json::json!([first.start, last.end]);
+    }
+    entry
+}
+
+/// Format file content with blame tags and line numbers if requested.
+/// Line numbers come first, so both prefixes read as "  1 | 2y alice | code".
+/// Reduced files show their original line numbers, always for grep windows
+/// and with --line-numbers for selected items and file parts (whose header
+/// shows the range). Duplicates show a reference to their representative.
+fn format_file_content(file: &ProcessedFile, config: &YekConfig) -> String {
+    if let Some(duplicate) = &file.duplicate {
+        return duplicate.describe();
+    }
+    let blamed;
+    let content = if file.blame.is_empty() {
+        &file.content
+    } else {
+        blamed = blame::format_content_with_blame(&file.content, &file.blame);
+        &blamed
+    };
+    if !file.windows.is_empty() {
+        if (file.selection.is_none() && file.part.is_none()) || config.line_numbers {
+            return snippets::format_windows(content, &file.windows);
+        }
+        return content.to_string();
     }
+    format_content_with_line_numbers(content, config.line_numbers)
 }
 
 /// Format file content with line numbers if requested
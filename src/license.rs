@@ -0,0 +1,191 @@
+use crate::models::ProcessedFile;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Minimum number of files sharing a header before it is stripped
+pub const MIN_REPEATS: usize = 3;
+
+/// Phrases that mark a comment block as a license or copyright header
+const LICENSE_MARKERS: &[&str] = &[
+    "spdx-license-identifier",
+    "licensed under",
+    "copyright",
+    "permission is hereby granted",
+    "all rights reserved",
+];
+
+/// A license header shared by several files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LicenseHeader {
+    /// Header text as it appears in the first file (by path)
+    pub text: String,
+    /// Number of files the header was stripped from
+    pub files: usize,
+}
+
+/// Strip license headers that open at least `MIN_REPEATS` files. Headers
+/// are compared ignoring whitespace and years, so `Copyright 2021` and
+/// `Copyright 2019-2024` count as the same header. The stripped text is
+/// kept in `license_header`. Returns the number of files changed.
+pub fn strip_license_headers(files: &mut [ProcessedFile]) -> usize {
+    let headers: Vec<Option<(usize, usize)>> = files
+        .par_iter()
+        .map(|file| {
+            if !file.windows.is_empty() {
+                return None;
+            }
+            license_block(&file.content)
+        })
+        .collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let keys: Vec<Option<String>> = files
+        .iter()
+        .zip(&headers)
+        .map(|(file, header)| {
+            let (start, end) = (*header)?;
+            let key = normalize(&lines_between(&file.content, start, end));
+            *counts.entry(key.clone()).or_default() += 1;
+            Some(key)
+        })
+        .collect();
+
+    let mut stripped = 0;
+    for ((file, header), key) in files.iter_mut().zip(headers).zip(keys) {
+        let (Some((start, end)), Some(key)) = (header, key) else {
+            continue;
+        };
+        if counts[&key] < MIN_REPEATS {
+            continue;
+        }
+        let lines: Vec<&str> = file.content.split_inclusive('\n').collect();
+        // Drop the blank lines separating the header from the code too
+        let mut until = end;
+        while until < lines.len() && lines[until].trim().is_empty() {
+            until += 1;
+        }
+        let header = lines_between(&file.content, start, end);
+        let content = lines[..start]
+            .iter()
+            .chain(&lines[until..])
+            .copied()
+            .collect::<String>();
+        file.replace_content(content);
+        if !file.blame.is_empty() {
+            file.blame.drain(start..until.min(file.blame.len()));
+        }
+        file.license_header = Some(header);
+        stripped += 1;
+    }
+    stripped
+}
+
+/// The distinct headers stripped from `files`, most common first
+pub fn collect_license_headers<'a>(
+    files: impl IntoIterator<Item = &'a ProcessedFile>,
+) -> Vec<LicenseHeader> {
+    let mut sorted: Vec<&ProcessedFile> = files
+        .into_iter()
+        .filter(|f| f.license_header.is_some())
+        .collect();
+    sorted.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+    let mut headers: Vec<LicenseHeader> = Vec::new();
+    let mut index_by_key: HashMap<String, usize> = HashMap::new();
+    for file in sorted {
+        let text = file.license_header.as_deref().unwrap_or_default();
+        let index = *index_by_key.entry(normalize(text)).or_insert_with(|| {
+            headers.push(LicenseHeader {
+                text: text.to_string(),
+                files: 0,
+            });
+            headers.len() - 1
+        });
+        headers[index].files += 1;
+    }
+    headers.sort_by_key(|header| std::cmp::Reverse(header.files));
+    headers
+}
+
+/// Render stripped license headers for the text output header
+pub fn render_license_headers(headers: &[LicenseHeader]) -> String {
+    let mut output = String::new();
+    for header in headers {
+        output.push_str(&format!(
+            "License header (stripped from {} files):\n{}\n\n",
+            header.files,
+            header.text.trim_end()
+        ));
+    }
+    output
+}
+
+/// Line range `[start, end)` of the first comment block of `content` if it
+/// looks like a license header. A shebang line before it is skipped.
+fn license_block(content: &str) -> Option<(usize, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut start = 0;
+    if lines.first().is_some_and(|line| line.starts_with("#!")) {
+        start = 1;
+    }
+    while start < lines.len() && lines[start].trim().is_empty() {
+        start += 1;
+    }
+
+    let mut end = start;
+    let mut in_block = false;
+    while end < lines.len() {
+        let line = lines[end].trim();
+        if in_block || line.starts_with("/*") || line.starts_with("<!--") {
+            in_block = !line.contains("*/") && !line.contains("-->");
+        } else if !is_line_comment(line) {
+            break;
+        }
+        end += 1;
+    }
+    if end == start || in_block {
+        return None;
+    }
+
+    let text = lines[start..end].join("\n").to_lowercase();
+    LICENSE_MARKERS
+        .iter()
+        .any(|marker| text.contains(marker))
+        .then_some((start, end))
+}
+
+/// Whether a trimmed line is a line comment (`//`, `#`, `--`, `;;`) or a
+/// `*` continuation line
+fn is_line_comment(line: &str) -> bool {
+    line.starts_with("//")
+        || line.starts_with("--")
+        || line.starts_with(";;")
+        || line.starts_with('*')
+        // `#` followed by text, but not `#include`, `#[derive]` or `#!`
+        || line == "#"
+        || line.starts_with("# ")
+        || line.starts_with("##")
+}
+
+/// Lines `start..end` of `content`, each ending with a newline
+fn lines_between(content: &str, start: usize, end: usize) -> String {
+    content
+        .split_inclusive('\n')
+        .skip(start)
+        .take(end - start)
+        .collect()
+}
+
+/// Comparison key for a header: whitespace collapsed, years replaced
+fn normalize(text: &str) -> String {
+    static YEARS: OnceLock<Regex> = OnceLock::new();
+    let years = YEARS.get_or_init(|| {
+        Regex::new(r"\b(?:19|20)\d{2}(?:\s*[-,–]\s*(?:(?:19|20)\d{2}|present))*\b")
+            .expect("valid regex")
+    });
+    let text = years.replace_all(text, "YEAR");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,496 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Lockfile names that can be summarized, with the manifest next to them
+/// that lists the direct dependencies (if the lockfile itself doesn't)
+const LOCKFILES: &[(&str, Option<&str>)] = &[
+    ("Cargo.lock", None),
+    ("package-lock.json", Some("package.json")),
+    ("npm-shrinkwrap.json", Some("package.json")),
+    ("yarn.lock", Some("package.json")),
+    ("poetry.lock", Some("pyproject.toml")),
+    ("go.sum", Some("go.mod")),
+];
+
+/// A resolved package in a lockfile
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Package {
+    name: String,
+    version: String,
+    /// Names of the packages it depends on
+    dependencies: Vec<String>,
+}
+
+/// Whether `path` is a lockfile yek can summarize
+pub fn is_lockfile(path: &str) -> bool {
+    lockfile_entry(path).is_some()
+}
+
+/// Names of the lockfiles yek can summarize
+pub fn lockfile_names() -> impl Iterator<Item = &'static str> {
+    LOCKFILES.iter().map(|(name, _)| *name)
+}
+
+/// The manifest read alongside a lockfile to find its direct dependencies
+pub fn manifest_name(path: &str) -> Option<&'static str> {
+    lockfile_entry(path).and_then(|(_, manifest)| manifest)
+}
+
+fn lockfile_entry(path: &str) -> Option<(&'static str, Option<&'static str>)> {
+    let name = Path::new(path).file_name()?.to_str()?;
+    LOCKFILES
+        .iter()
+        .copied()
+        .find(|(lockfile, _)| *lockfile == name)
+}
+
+/// Replace a lockfile with a compact list of the resolved packages and
+/// versions, split into direct and transitive dependencies. `manifest` is
+/// the content of the file named by `manifest_name`, if there is one.
+/// Returns `None` if the file is not a supported lockfile or can't be parsed.
+pub fn summarize_lockfile(path: &str, content: &str, manifest: Option<&str>) -> Option<String> {
+    let (name, _) = lockfile_entry(path)?;
+    let (packages, direct) = match name {
+        "Cargo.lock" => parse_cargo_lock(content)?,
+        "package-lock.json" | "npm-shrinkwrap.json" => parse_package_lock(content, manifest)?,
+        "yarn.lock" => parse_yarn_lock(content, manifest),
+        "poetry.lock" => parse_poetry_lock(content, manifest)?,
+        "go.sum" => parse_go_sum(content, manifest),
+        _ => return None,
+    };
+    if packages.is_empty() {
+        return None;
+    }
+    Some(render(name, &packages, direct))
+}
+
+/// Packages no other package depends on, for lockfiles without a record of
+/// what the project itself requires
+fn roots(packages: &[Package]) -> HashSet<String> {
+    let required: HashSet<&str> = packages
+        .iter()
+        .flat_map(|package| package.dependencies.iter().map(String::as_str))
+        .collect();
+    packages
+        .iter()
+        .filter(|package| !required.contains(package.name.as_str()))
+        .map(|package| package.name.clone())
+        .collect()
+}
+
+/// Render the summary: direct and transitive packages by name, each with
+/// every version that was resolved. Without `direct`, packages are listed
+/// together.
+fn render(lockfile: &str, packages: &[Package], direct: Option<HashSet<String>>) -> String {
+    let mut versions: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for package in packages {
+        let entry = versions.entry(&package.name).or_default();
+        if !entry.contains(&package.version.as_str()) {
+            entry.push(&package.version);
+        }
+    }
+    let line =
+        |(name, versions): (&&str, &Vec<&str>)| format!("  {} {}\n", name, versions.join(", "));
+
+    let mut output = String::new();
+    match direct {
+        Some(direct) => {
+            let (direct_lines, transitive_lines): (Vec<_>, Vec<_>) = versions
+                .iter()
+                .partition(|(name, _)| direct.contains(**name));
+            output.push_str(&format!(
+                "{} summary: {} direct and {} transitive packages\n",
+                lockfile,
+                direct_lines.len(),
+                transitive_lines.len()
+            ));
+            for (title, lines) in [("direct", direct_lines), ("transitive", transitive_lines)] {
+                if !lines.is_empty() {
+                    output.push_str(&format!("{}:\n", title));
+                    output.extend(lines.into_iter().map(line));
+                }
+            }
+        }
+        None => {
+            output.push_str(&format!(
+                "{} summary: {} packages\n",
+                lockfile,
+                versions.len()
+            ));
+            output.extend(versions.iter().map(line));
+        }
+    }
+    output
+}
+
+/// `Cargo.lock`: workspace members are the packages without a `source`,
+/// and their dependencies are the direct ones
+fn parse_cargo_lock(content: &str) -> Option<(Vec<Package>, Option<HashSet<String>>)> {
+    let lock: toml::Table = toml::from_str(content).ok()?;
+    let mut packages = Vec::new();
+    let mut members = Vec::new();
+    for entry in lock.get("package")?.as_array()? {
+        let name = entry.get("name")?.as_str()?.to_string();
+        let version = entry.get("version")?.as_str()?.to_string();
+        // Entries are "name", "name version" or "name version (source)"
+        let dependencies = entry
+            .get("dependencies")
+            .and_then(|deps| deps.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|dep| dep.as_str()?.split_whitespace().next())
+            .map(str::to_string)
+            .collect();
+        let package = Package {
+            name,
+            version,
+            dependencies,
+        };
+        if entry.get("source").is_some() {
+            packages.push(package);
+        } else {
+            members.push(package);
+        }
+    }
+    let member_names: HashSet<&str> = members.iter().map(|m| m.name.as_str()).collect();
+    let direct = members
+        .iter()
+        .flat_map(|member| member.dependencies.iter())
+        .filter(|dep| !member_names.contains(dep.as_str()))
+        .cloned()
+        .collect();
+    Some((packages, Some(direct)))
+}
+
+/// `package-lock.json`: version 2 and 3 list every installed path under
+/// `packages`, with the project itself under `""`; version 1 nests
+/// `dependencies`
+fn parse_package_lock(
+    content: &str,
+    manifest: Option<&str>,
+) -> Option<(Vec<Package>, Option<HashSet<String>>)> {
+    let lock: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut packages = Vec::new();
+
+    if let Some(installed) = lock.get("packages").and_then(|p| p.as_object()) {
+        for (path, entry) in installed {
+            // Skip the project, workspace folders and links to them
+            let Some(index) = path.rfind("node_modules/") else {
+                continue;
+            };
+            if entry.get("link").is_some() {
+                continue;
+            }
+            let Some(version) = entry.get("version").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            packages.push(Package {
+                name: path[index + "node_modules/".len()..].to_string(),
+                version: version.to_string(),
+                dependencies: json_keys(entry, &["dependencies", "optionalDependencies"]),
+            });
+        }
+        let direct = installed
+            .get("")
+            .map(npm_direct_dependencies)
+            .or_else(|| manifest.and_then(package_json_dependencies))
+            .unwrap_or_else(|| roots(&packages));
+        return Some((packages, Some(direct)));
+    }
+
+    fn walk(dependencies: &serde_json::Value, packages: &mut Vec<Package>) {
+        for (name, entry) in dependencies.as_object().into_iter().flatten() {
+            if let Some(version) = entry.get("version").and_then(|v| v.as_str()) {
+                packages.push(Package {
+                    name: name.clone(),
+                    version: version.to_string(),
+                    dependencies: json_keys(entry, &["requires"]),
+                });
+            }
+            if let Some(nested) = entry.get("dependencies") {
+                walk(nested, packages);
+            }
+        }
+    }
+    walk(lock.get("dependencies")?, &mut packages);
+    let direct = manifest
+        .and_then(package_json_dependencies)
+        .unwrap_or_else(|| roots(&packages));
+    Some((packages, Some(direct)))
+}
+
+/// Keys of the given objects of a JSON value
+fn json_keys(value: &serde_json::Value, fields: &[&str]) -> Vec<String> {
+    fields
+        .iter()
+        .filter_map(|field| value.get(field)?.as_object())
+        .flat_map(|object| object.keys().cloned())
+        .collect()
+}
+
+/// Dependencies declared by a `package.json` (or the root of a lockfile)
+fn npm_direct_dependencies(package: &serde_json::Value) -> HashSet<String> {
+    json_keys(
+        package,
+        &[
+            "dependencies",
+            "devDependencies",
+            "optionalDependencies",
+            "peerDependencies",
+        ],
+    )
+    .into_iter()
+    .collect()
+}
+
+fn package_json_dependencies(manifest: &str) -> Option<HashSet<String>> {
+    let package: serde_json::Value = serde_json::from_str(manifest).ok()?;
+    Some(npm_direct_dependencies(&package))
+}
+
+/// `yarn.lock`, in the classic (`version "1.0.0"`) and Berry
+/// (`version: 1.0.0`) formats
+fn parse_yarn_lock(
+    content: &str,
+    manifest: Option<&str>,
+) -> (Vec<Package>, Option<HashSet<String>>) {
+    let mut packages: Vec<Package> = Vec::new();
+    let mut workspace_dependencies: Option<HashSet<String>> = None;
+    let mut current: Option<Package> = None;
+    let mut is_workspace = false;
+    let mut in_dependencies = false;
+
+    let mut finish = |package: Option<Package>, is_workspace: bool| {
+        if let Some(package) = package {
+            if is_workspace {
+                workspace_dependencies
+                    .get_or_insert_with(HashSet::new)
+                    .extend(package.dependencies);
+            } else if !package.version.is_empty() {
+                packages.push(package);
+            }
+        }
+    };
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+        if indent == 0 {
+            finish(current.take(), is_workspace);
+            in_dependencies = false;
+            // The first of the comma-separated specs names the package
+            let spec = line
+                .trim_end_matches(':')
+                .split(", ")
+                .next()
+                .unwrap_or_default()
+                .trim_matches('"');
+            if spec == "__metadata" {
+                continue;
+            }
+            is_workspace = spec.contains("@workspace:") || spec.contains("@link:");
+            current = Some(Package {
+                name: yarn_package_name(spec).to_string(),
+                version: String::new(),
+                dependencies: Vec::new(),
+            });
+            continue;
+        }
+        let Some(package) = current.as_mut() else {
+            continue;
+        };
+        if indent <= 2 {
+            in_dependencies = line == "dependencies:" || line == "optionalDependencies:";
+            if let Some(version) = line.strip_prefix("version") {
+                package.version = version
+                    .trim_start_matches(':')
+                    .trim()
+                    .trim_matches('"')
+                    .to_string();
+            }
+        } else if in_dependencies {
+            let name = line
+                .split(|c: char| c == ':' || c.is_whitespace())
+                .find(|part| !part.is_empty())
+                .unwrap_or_default();
+            package
+                .dependencies
+                .push(name.trim_matches('"').to_string());
+        }
+    }
+    finish(current.take(), is_workspace);
+
+    let direct = manifest
+        .and_then(package_json_dependencies)
+        .or(workspace_dependencies)
+        .unwrap_or_else(|| roots(&packages));
+    (packages, Some(direct))
+}
+
+/// Name of a yarn spec such as `@scope/name@^1.0.0` or `name@npm:1.0.0`
+fn yarn_package_name(spec: &str) -> &str {
+    match spec.get(1..).and_then(|rest| rest.find('@')) {
+        Some(index) => &spec[..index + 1],
+        None => spec,
+    }
+}
+
+/// `poetry.lock`, with the direct dependencies taken from `pyproject.toml`
+fn parse_poetry_lock(
+    content: &str,
+    manifest: Option<&str>,
+) -> Option<(Vec<Package>, Option<HashSet<String>>)> {
+    let lock: toml::Table = toml::from_str(content).ok()?;
+    let mut packages = Vec::new();
+    for entry in lock.get("package")?.as_array()? {
+        packages.push(Package {
+            name: normalize_python_name(entry.get("name")?.as_str()?),
+            version: entry.get("version")?.as_str()?.to_string(),
+            dependencies: entry
+                .get("dependencies")
+                .and_then(|deps| deps.as_table())
+                .into_iter()
+                .flat_map(|deps| deps.keys())
+                .map(|name| normalize_python_name(name))
+                .collect(),
+        });
+    }
+    let direct = manifest
+        .and_then(pyproject_dependencies)
+        .unwrap_or_else(|| roots(&packages));
+    Some((packages, Some(direct)))
+}
+
+/// Python package names compare case-insensitively, with `-`, `_` and `.`
+/// being equivalent
+fn normalize_python_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Dependencies declared in a `pyproject.toml`, from Poetry's tables or
+/// the standard `[project]` table
+fn pyproject_dependencies(manifest: &str) -> Option<HashSet<String>> {
+    let pyproject: toml::Table = toml::from_str(manifest).ok()?;
+    let mut direct = HashSet::new();
+
+    if let Some(poetry) = pyproject.get("tool").and_then(|tool| tool.get("poetry")) {
+        let groups = poetry
+            .get("group")
+            .and_then(|groups| groups.as_table())
+            .into_iter()
+            .flat_map(|groups| groups.values())
+            .filter_map(|group| group.get("dependencies"));
+        let tables = ["dependencies", "dev-dependencies"]
+            .iter()
+            .filter_map(|table| poetry.get(table))
+            .chain(groups)
+            .filter_map(|table| table.as_table());
+        for table in tables {
+            direct.extend(
+                table
+                    .keys()
+                    .filter(|name| name.as_str() != "python")
+                    .map(|name| normalize_python_name(name)),
+            );
+        }
+    }
+
+    if let Some(project) = pyproject.get("project") {
+        let optional = project
+            .get("optional-dependencies")
+            .and_then(|extras| extras.as_table())
+            .into_iter()
+            .flat_map(|extras| extras.values());
+        let requirements = project
+            .get("dependencies")
+            .into_iter()
+            .chain(optional)
+            .filter_map(|list| list.as_array())
+            .flatten()
+            .filter_map(|requirement| requirement.as_str());
+        for requirement in requirements {
+            // "requests[socks]>=2.0; python_version > '3.8'" names "requests"
+            let name = requirement
+                .split(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .next()
+                .unwrap_or_default();
+            if !name.is_empty() {
+                direct.insert(normalize_python_name(name));
+            }
+        }
+    }
+
+    Some(direct)
+}
+
+/// `go.sum` lists checksums of every module version the build may need.
+/// The `go.mod` next to it records the selected versions, with indirect
+/// dependencies marked `// indirect`; without it, every version in
+/// `go.sum` is listed.
+fn parse_go_sum(content: &str, manifest: Option<&str>) -> (Vec<Package>, Option<HashSet<String>>) {
+    if let Some((packages, direct)) = manifest.and_then(parse_go_mod) {
+        return (packages, Some(direct));
+    }
+    let packages = content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let module = fields.next()?;
+            let version = fields.next()?;
+            // Lines for a module's go.mod alone don't mean its code is used
+            if version.ends_with("/go.mod") {
+                return None;
+            }
+            Some(Package {
+                name: module.to_string(),
+                version: version.to_string(),
+                dependencies: Vec::new(),
+            })
+        })
+        .collect();
+    (packages, None)
+}
+
+/// The `require` directives of a `go.mod`, and which of them are direct
+fn parse_go_mod(manifest: &str) -> Option<(Vec<Package>, HashSet<String>)> {
+    let mut packages = Vec::new();
+    let mut direct = HashSet::new();
+    let mut in_block = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        let requirement = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(requirement) = line.strip_prefix("require ") {
+            requirement
+        } else {
+            continue;
+        };
+        let (requirement, comment) = match requirement.split_once("//") {
+            Some((requirement, comment)) => (requirement, comment.trim()),
+            None => (requirement, ""),
+        };
+        let mut fields = requirement.split_whitespace();
+        let (Some(module), Some(version)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if comment != "indirect" {
+            direct.insert(module.to_string());
+        }
+        packages.push(Package {
+            name: module.to_string(),
+            version: version.to_string(),
+            dependencies: Vec::new(),
+        });
+    }
+    (!packages.is_empty()).then_some((packages, direct))
+}
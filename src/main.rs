@@ -88,7 +88,13 @@ fn main() -> Result<()> {
                 output_name.clone()
             }
         } else {
-            let extension = if full_config.json { "json" } else { "txt" };
+            let extension = if full_config.json {
+                "json"
+            } else if full_config.format.is_some() {
+                "jsonl"
+            } else {
+                "txt"
+            };
             let output_dir = full_config.output_dir.as_ref().ok_or_else(|| {
                 anyhow::anyhow!("Output directory is required when not in streaming mode. This may indicate a configuration validation error.")
             })?;
@@ -0,0 +1,297 @@
+use crate::{lang::Language, models::ProcessedFile, outline::skip_literal};
+use rayon::prelude::*;
+
+/// Comment and string syntax of a language, as far as minification needs it
+struct Syntax {
+    line_comment: Option<&'static str>,
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Whether block comments nest
+    nested_comments: bool,
+    /// Whether `'` starts a string (rather than a character or prime)
+    single_quotes: bool,
+    /// Whether `"""` and `'''` start multi-line strings
+    triple_quotes: bool,
+}
+
+impl Syntax {
+    fn of(language: Language) -> Syntax {
+        let block_comment = match language {
+            Language::Python | Language::Ruby | Language::Shell => None,
+            Language::Haskell => Some(("{-", "-}")),
+            Language::Lua => Some(("--[[", "]]")),
+            Language::Html => Some(("<!--", "-->")),
+            _ => Some(("/*", "*/")),
+        };
+        Syntax {
+            line_comment: language.line_comment(),
+            block_comment,
+            nested_comments: matches!(
+                language,
+                Language::Rust
+                    | Language::Haskell
+                    | Language::Kotlin
+                    | Language::Scala
+                    | Language::Swift
+                    | Language::Dart
+            ),
+            single_quotes: !matches!(language, Language::Haskell | Language::Html),
+            triple_quotes: matches!(
+                language,
+                Language::Python | Language::Kotlin | Language::Scala | Language::Swift
+            ),
+        }
+    }
+}
+
+/// Minify every complete source file in `files`. Level 1 drops trailing
+/// whitespace and blank lines; level 2 also strips comments, keeping doc
+/// comments if `keep_doc_comments` is set. String literals are never
+/// changed. Files in languages yek cannot parse, and files already reduced
+/// to line windows, are left as they are.
+pub fn minify_files(files: &mut [ProcessedFile], level: u8, keep_doc_comments: bool) {
+    files.par_iter_mut().for_each(|file| {
+        if !file.windows.is_empty() {
+            return;
+        }
+        let Some(language) = Language::from_path(&file.rel_path) else {
+            return;
+        };
+        let minified = minify(&file.content, language, level, keep_doc_comments);
+        if minified != file.content {
+            file.replace_content(minified);
+            // Per-line annotations no longer line up with the content
+            file.blame.clear();
+        }
+    });
+}
+
+/// Minify source code at the given level (1 or 2)
+pub fn minify(content: &str, language: Language, level: u8, keep_doc_comments: bool) -> String {
+    let syntax = Syntax::of(language);
+    let chars: Vec<char> = content.chars().collect();
+
+    // Scan once, dropping comments (level 2) and remembering which
+    // newlines are inside string literals and must be kept as they are
+    let mut code = String::with_capacity(content.len());
+    let mut literal_newlines: Vec<bool> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(end) = comment_end(&chars, i, &syntax) {
+            let comment: String = chars[i..end].iter().collect();
+            let keep = level < 2
+                || (keep_doc_comments && is_doc_comment(&comment, language))
+                || (i == 0 && comment.starts_with("#!"));
+            if keep {
+                code.push_str(&comment);
+            }
+            // Keep the line structure of multi-line comments
+            for _ in comment.matches('\n') {
+                if !keep {
+                    code.push('\n');
+                }
+                literal_newlines.push(false);
+            }
+            i = end;
+            continue;
+        }
+        if let Some(end) = literal_end(&chars, i, language, &syntax) {
+            for &c in &chars[i..end] {
+                code.push(c);
+                if c == '\n' {
+                    literal_newlines.push(true);
+                }
+            }
+            i = end;
+            continue;
+        }
+        code.push(chars[i]);
+        if chars[i] == '\n' {
+            literal_newlines.push(false);
+        }
+        i += 1;
+    }
+
+    // Trim lines and drop blank ones, except where a literal spans the line
+    let mut lines: Vec<&str> = Vec::new();
+    for (index, line) in code.split('\n').enumerate() {
+        let starts_in_literal = index > 0 && literal_newlines[index - 1];
+        let ends_in_literal = literal_newlines.get(index).copied().unwrap_or(false);
+        if ends_in_literal {
+            lines.push(line);
+        } else if starts_in_literal || !line.trim().is_empty() {
+            lines.push(line.trim_end());
+        }
+    }
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') && !output.is_empty() {
+        output.push('\n');
+    }
+    output
+}
+
+/// If a comment starts at `chars[i]`, the index just past it. Line
+/// comments end before their newline.
+fn comment_end(chars: &[char], i: usize, syntax: &Syntax) -> Option<usize> {
+    let starts_with = |marker: &str| {
+        marker
+            .chars()
+            .enumerate()
+            .all(|(k, c)| chars.get(i + k) == Some(&c))
+    };
+    // Block comments first: Lua's `--[[` also starts with the line marker
+    if let Some((open, close)) = syntax.block_comment {
+        if starts_with(open) {
+            let (open, close): (Vec<char>, Vec<char>) =
+                (open.chars().collect(), close.chars().collect());
+            let mut depth = 0;
+            let mut j = i;
+            while j < chars.len() {
+                if (depth == 0 || syntax.nested_comments) && chars[j..].starts_with(&open) {
+                    depth += 1;
+                    j += open.len();
+                } else if chars[j..].starts_with(&close) {
+                    depth -= 1;
+                    j += close.len();
+                    if depth == 0 {
+                        return Some(j);
+                    }
+                } else {
+                    j += 1;
+                }
+            }
+            return Some(chars.len());
+        }
+    }
+    let marker = syntax.line_comment?;
+    if !starts_with(marker) {
+        return None;
+    }
+    // `#` only starts a comment at the start of a word (not in `$#` or `a#b`)
+    if marker == "#" && i > 0 && !chars[i - 1].is_whitespace() && chars[i - 1] != ';' {
+        return None;
+    }
+    Some(
+        (i..chars.len())
+            .find(|&j| chars[j] == '\n')
+            .unwrap_or(chars.len()),
+    )
+}
+
+/// If a string literal starts at `chars[i]`, the index just past it
+fn literal_end(chars: &[char], i: usize, language: Language, syntax: &Syntax) -> Option<usize> {
+    if matches!(
+        language,
+        Language::Rust | Language::Go | Language::JavaScript | Language::TypeScript
+    ) {
+        // Comments are handled by the caller, so only strings remain here
+        return skip_literal(chars, i, language);
+    }
+    if language == Language::Shell {
+        if let Some(end) = heredoc_end(chars, i) {
+            return Some(end);
+        }
+    }
+    let at = |j: usize| chars.get(j).copied();
+    let quote = chars[i];
+    if quote != '"' && !(quote == '\'' && syntax.single_quotes) {
+        return None;
+    }
+    if syntax.triple_quotes && at(i + 1) == Some(quote) && at(i + 2) == Some(quote) {
+        let end = (i + 3..chars.len())
+            .find(|&j| chars[j] == quote && at(j + 1) == Some(quote) && at(j + 2) == Some(quote))
+            .map(|j| j + 3)
+            .unwrap_or(chars.len());
+        return Some(end);
+    }
+    // Shell single quotes have no escapes
+    let escapes = !(language == Language::Shell && quote == '\'');
+    let mut j = i + 1;
+    while j < chars.len() {
+        match chars[j] {
+            '\\' if escapes => j += 2,
+            c if c == quote => return Some(j + 1),
+            _ => j += 1,
+        }
+    }
+    Some(chars.len())
+}
+
+/// If a shell here-document starts at `chars[i]` (`<<EOF`, `<<-'EOF'`),
+/// the index just past its terminating line. The rest of the `<<` line is
+/// kept along with the body.
+fn heredoc_end(chars: &[char], i: usize) -> Option<usize> {
+    // `<<<` is a here-string
+    if !chars[i..].starts_with(&['<', '<'])
+        || chars.get(i + 2) == Some(&'<')
+        || (i > 0 && chars[i - 1] == '<')
+    {
+        return None;
+    }
+    let mut j = i + 2;
+    let strip_tabs = chars.get(j) == Some(&'-');
+    if strip_tabs {
+        j += 1;
+    }
+    while chars.get(j).is_some_and(|&c| c == ' ' || c == '\t') {
+        j += 1;
+    }
+    let quote = match chars.get(j) {
+        Some(&quote @ ('\'' | '"')) => Some(quote),
+        _ => None,
+    };
+    // `<<'EOF'`, `<<"EOF"` and `<<\EOF` only turn off expansion
+    if quote.is_some() || chars.get(j) == Some(&'\\') {
+        j += 1;
+    }
+    let start = j;
+    while chars
+        .get(j)
+        .is_some_and(|&c| c.is_alphanumeric() || c == '_')
+    {
+        j += 1;
+    }
+    let delimiter: String = chars[start..j].iter().collect();
+    // Not `$((1<<2))`
+    if !delimiter.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        return None;
+    }
+    if quote.is_some_and(|quote| chars.get(j) != Some(&quote)) {
+        return None;
+    }
+    let mut line_start = (j..chars.len()).find(|&k| chars[k] == '\n')? + 1;
+    while line_start < chars.len() {
+        let line_end = (line_start..chars.len())
+            .find(|&k| chars[k] == '\n')
+            .unwrap_or(chars.len());
+        let line: String = chars[line_start..line_end].iter().collect();
+        let line = if strip_tabs {
+            line.trim_start_matches('\t')
+        } else {
+            &line
+        };
+        if line == delimiter {
+            return Some(line_end);
+        }
+        line_start = line_end + 1;
+    }
+    Some(chars.len())
+}
+
+/// Whether a comment documents an item: `///`, `//!`, `/** */` and `/*! */`
+/// in C-like languages, `-- |` and `{-|` in Haskell, `---` in Lua
+fn is_doc_comment(comment: &str, language: Language) -> bool {
+    match language {
+        Language::Python | Language::Ruby | Language::Shell => false,
+        Language::Haskell => {
+            comment.starts_with("{-|") || comment.starts_with("-- |") || comment.starts_with("-- ^")
+        }
+        Language::Lua => comment.starts_with("---") && !comment.starts_with("--[["),
+        Language::Html => false,
+        _ => {
+            (comment.starts_with("///") && !comment.starts_with("////"))
+                || comment.starts_with("//!")
+                || (comment.starts_with("/**") && comment != "/**/")
+                || comment.starts_with("/*!")
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use std::sync::OnceLock;
 
 use crate::category::FileCategory;
+use crate::git::CommitSummary;
 
 /// Represents a processed file with its metadata and content
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,6 +25,76 @@ pub struct ProcessedFile {
     pub formatted_content: Option<String>,
     /// File category for improved sorting and organization
     pub category: FileCategory,
+    /// Most recent commits touching this file (empty unless requested)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<CommitSummary>,
+    /// Per-line blame tags (age and author), empty unless requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blame: Vec<String>,
+    /// Git working-tree status code (`M`, `A`, `??`, `R`), if the file has uncommitted changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_status: Option<String>,
+    /// Original line ranges kept when the content was reduced; `content` then
+    /// holds only those lines. Empty when the file is complete.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<LineWindow>,
+    /// Part of the file addressed by the input path, shown in the file header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<Selection>,
+    /// Position of this entry when an oversized file was split into parts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part: Option<FilePart>,
+    /// License header stripped from the top of the content, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_header: Option<String>,
+    /// The file this one duplicates; its content is then left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate: Option<Duplicate>,
+}
+
+/// Reference from a duplicate file to the representative included in full
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Duplicate {
+    /// Path of the representative file
+    pub of: String,
+    /// Estimated similarity in percent; 100 for identical content
+    pub similarity: u8,
+}
+
+impl Duplicate {
+    /// Human-readable reference, e.g. `identical to a.json` or `95% similar to a.json`
+    pub fn describe(&self) -> String {
+        if self.similarity == 100 {
+            format!("identical to {}", self.of)
+        } else {
+            format!("{}% similar to {}", self.similarity, self.of)
+        }
+    }
+}
+
+/// One of several consecutive parts of a split file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilePart {
+    /// 1-based index of the part
+    pub index: usize,
+    /// Total number of parts
+    pub count: usize,
+}
+
+/// How part of a file was addressed by an input path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Selection {
+    /// A named code item such as `concat_files` or `YekConfig::validate`
+    Symbol(String),
+    /// A range of lines (1-based, inclusive)
+    Lines { start: usize, end: usize },
+}
+
+/// A range of original line numbers (1-based, inclusive) kept from a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineWindow {
+    pub start: usize,
+    pub end: usize,
 }
 
 impl Clone for ProcessedFile {
@@ -37,6 +108,14 @@ impl Clone for ProcessedFile {
             token_count: OnceLock::new(),
             formatted_content: self.formatted_content.clone(),
             category: self.category,
+            history: self.history.clone(),
+            blame: self.blame.clone(),
+            git_status: self.git_status.clone(),
+            windows: self.windows.clone(),
+            selection: self.selection.clone(),
+            part: self.part,
+            license_header: self.license_header.clone(),
+            duplicate: self.duplicate.clone(),
         }
     }
 }
@@ -55,6 +134,14 @@ impl ProcessedFile {
             token_count: OnceLock::new(),
             formatted_content: None,
             category,
+            history: Vec::new(),
+            blame: Vec::new(),
+            git_status: None,
+            windows: Vec::new(),
+            selection: None,
+            part: None,
+            license_header: None,
+            duplicate: None,
         }
     }
 
@@ -76,6 +163,14 @@ impl ProcessedFile {
             token_count: OnceLock::new(),
             formatted_content: None,
             category,
+            history: Vec::new(),
+            blame: Vec::new(),
+            git_status: None,
+            windows: Vec::new(),
+            selection: None,
+            part: None,
+            license_header: None,
+            duplicate: None,
         }
     }
 
@@ -162,6 +257,13 @@ impl ProcessedFile {
         self.get_size(token_mode, include_line_numbers) > limit
     }
 
+    /// Replace the content, keeping size and cached values consistent
+    pub fn replace_content(&mut self, content: String) {
+        self.size_bytes = content.len();
+        self.content = content;
+        self.clear_caches();
+    }
+
     /// Clear caches to free memory
     pub fn clear_caches(&mut self) {
         self.token_count = OnceLock::new();
@@ -290,6 +392,28 @@ pub struct ProcessingConfig {
     pub priority_rules: Vec<crate::priority::PriorityRule>,
     /// Category-based priority weights
     pub category_weights: crate::category::CategoryWeights,
+    /// Whether to keep generated and minified files found in directories
+    pub include_generated: bool,
+    /// Whether to replace lockfiles with a summary of their packages
+    pub summarize_lockfiles: bool,
+    /// Size in bytes above which data files are replaced with a summary
+    pub summarize_data_above: Option<usize>,
+    /// Number of records kept in data file summaries
+    pub data_sample: usize,
+    /// Lines of text output kept per notebook cell output
+    pub notebook_outputs: usize,
+    /// Whether to extract the text of PDF, DOCX and ODT documents
+    pub extract_documents: bool,
+    /// Maximum size in bytes of the text extracted from a document
+    pub document_max_size: usize,
+    /// Whether to replace SQLite databases with their schema
+    #[cfg(feature = "sqlite")]
+    pub sqlite_schema: bool,
+    /// Number of rows kept per table of a SQLite database
+    #[cfg(feature = "sqlite")]
+    pub sqlite_rows: usize,
+    /// Whether to include binary files as placeholders
+    pub binary_placeholders: bool,
     /// Whether to enable debug output
     pub debug: bool,
     /// Whether to enable parallel processing
@@ -307,6 +431,18 @@ impl Default for ProcessingConfig {
         Self {
             priority_rules: Vec::new(),
             category_weights: crate::category::CategoryWeights::default(),
+            include_generated: false,
+            summarize_lockfiles: false,
+            summarize_data_above: None,
+            data_sample: 3,
+            notebook_outputs: 0,
+            extract_documents: false,
+            document_max_size: 256 * 1024,
+            #[cfg(feature = "sqlite")]
+            sqlite_schema: false,
+            #[cfg(feature = "sqlite")]
+            sqlite_rows: 0,
+            binary_placeholders: false,
             debug: false,
             parallel: true,
             max_threads: None,
@@ -0,0 +1,161 @@
+use serde_json::Value;
+use std::path::Path;
+
+/// Whether `path` is a Jupyter notebook
+pub fn is_notebook(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ipynb"))
+}
+
+/// Render a Jupyter notebook in the percent format: cells in order, each
+/// opened by a `# %%` marker (`# %% [markdown]` for Markdown cells, whose
+/// lines are commented out). The language, and so the comment marker, is
+/// taken from the kernel metadata. Text outputs of code cells are kept up to
+/// `output_lines` lines each; images, HTML and other rich outputs are
+/// dropped. Returns `None` if the content is not a notebook.
+pub fn render_notebook(content: &str, output_lines: usize) -> Option<String> {
+    let notebook: Value = serde_json::from_str(content).ok()?;
+    let cells = notebook.get("cells")?.as_array()?;
+    let language = notebook_language(&notebook);
+    let comment = comment_marker(&language);
+
+    let mut output = format!("{} Jupyter notebook ({})\n", comment, language);
+    for cell in cells {
+        let source = text(cell.get("source"));
+        let source = source.trim_end_matches('\n');
+        output.push('\n');
+        match cell.get("cell_type").and_then(|t| t.as_str()) {
+            Some("code") => {
+                output.push_str(&format!("{} %%\n", comment));
+                if !source.is_empty() {
+                    output.push_str(source);
+                    output.push('\n');
+                }
+                if output_lines > 0 {
+                    for text in cell
+                        .get("outputs")
+                        .and_then(|o| o.as_array())
+                        .into_iter()
+                        .flatten()
+                        .filter_map(output_text)
+                    {
+                        push_output(&text, comment, output_lines, &mut output);
+                    }
+                }
+            }
+            Some(kind) => {
+                output.push_str(&format!("{} %% [{}]\n", comment, kind));
+                for line in source.lines() {
+                    output.push_str(&commented(line, comment));
+                }
+            }
+            None => continue,
+        }
+    }
+    Some(output)
+}
+
+/// Number of (code, other) cells in a notebook rendered by `render_notebook`
+pub fn count_cells(rendered: &str) -> (usize, usize) {
+    let mut counts = (0, 0);
+    for line in rendered.lines() {
+        let Some((marker, rest)) = line.split_once(" %%") else {
+            continue;
+        };
+        // Markers are a comment marker alone, followed by nothing or a kind
+        if marker.is_empty()
+            || marker
+                .chars()
+                .any(|c| c.is_alphanumeric() || c.is_whitespace())
+        {
+            continue;
+        }
+        if rest.is_empty() {
+            counts.0 += 1;
+        } else if rest.starts_with(" [") && rest.ends_with(']') {
+            counts.1 += 1;
+        }
+    }
+    counts
+}
+
+/// The notebook's language, from the kernel spec or the language info
+fn notebook_language(notebook: &Value) -> String {
+    let metadata = notebook.get("metadata");
+    metadata
+        .and_then(|m| m.get("kernelspec"))
+        .and_then(|k| k.get("language"))
+        .or_else(|| {
+            metadata
+                .and_then(|m| m.get("language_info"))
+                .and_then(|l| l.get("name"))
+        })
+        .and_then(|name| name.as_str())
+        .map(|name| name.to_lowercase())
+        .unwrap_or_else(|| "python".to_string())
+}
+
+/// Line comment marker of a kernel language
+fn comment_marker(language: &str) -> &'static str {
+    match language {
+        "c" | "c++" | "cpp" | "c#" | "csharp" | "f#" | "fsharp" | "go" | "java" | "javascript"
+        | "kotlin" | "rust" | "scala" | "swift" | "typescript" => "//",
+        "haskell" | "lua" | "sql" => "--",
+        "matlab" | "octave" => "%",
+        _ => "#",
+    }
+}
+
+/// Notebook text fields are either a string or a list of lines
+fn text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(|l| l.as_str()).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Plain text of a cell output: a stream, the `text/plain` form of a result
+/// or display, or the message of an error
+fn output_text(output: &Value) -> Option<String> {
+    let text = match output.get("output_type")?.as_str()? {
+        "stream" => text(output.get("text")),
+        "execute_result" | "display_data" => text(output.get("data")?.get("text/plain")),
+        "error" => format!(
+            "{}: {}",
+            output.get("ename")?.as_str()?,
+            output
+                .get("evalue")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+        ),
+        _ => return None,
+    };
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// Append an output as comment lines, truncated to `max_lines`
+fn push_output(text: &str, comment: &str, max_lines: usize, output: &mut String) {
+    let lines: Vec<&str> = text.trim_end_matches('\n').lines().collect();
+    output.push_str(&format!("{} Out:\n", comment));
+    for line in lines.iter().take(max_lines) {
+        output.push_str(&commented(line, comment));
+    }
+    if lines.len() > max_lines {
+        output.push_str(&format!(
+            "{} ... ({} more lines)\n",
+            comment,
+            lines.len() - max_lines
+        ));
+    }
+}
+
+fn commented(line: &str, comment: &str) -> String {
+    if line.is_empty() {
+        format!("{}\n", comment)
+    } else {
+        format!("{} {}\n", comment, line)
+    }
+}
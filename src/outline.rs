@@ -0,0 +1,354 @@
+use crate::{lang::Language, models::ProcessedFile};
+use rayon::prelude::*;
+use regex::Regex;
+use std::sync::OnceLock;
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid outline regex"))
+}
+
+/// Reduce source code to a skeleton: declarations, signatures and doc
+/// comments, with function bodies replaced by `{ ... }` (`...` in Python).
+/// Returns None for languages without outline support.
+pub fn outline(path: &str, content: &str) -> Option<String> {
+    let language = Language::from_path(path)?;
+    match language {
+        Language::Rust | Language::Go | Language::JavaScript | Language::TypeScript => {
+            Some(outline_braces(content, language))
+        }
+        Language::Python => Some(outline_python(content)),
+        _ => None,
+    }
+}
+
+/// Outline every file in `files` whose priority is below `threshold`
+/// (all files when no threshold is given). Outlining replaces the content,
+/// so size and token budgeting use the reduced text. Files already reduced
+/// to line windows are left as they are.
+pub fn outline_files(files: &mut [ProcessedFile], threshold: Option<i32>) {
+    files.par_iter_mut().for_each(|file| {
+        if !file.windows.is_empty() || threshold.is_some_and(|t| file.priority >= t) {
+            return;
+        }
+        if let Some(outlined) = outline(&file.rel_path, &file.content) {
+            file.replace_content(outlined);
+            // Per-line annotations no longer line up with the content
+            file.blame.clear();
+        }
+    });
+}
+
+/// If `chars[i]` starts a comment or string literal, return the index just past it
+pub(crate) fn skip_literal(chars: &[char], i: usize, language: Language) -> Option<usize> {
+    let at = |j: usize| chars.get(j).copied();
+    let find_from = |start: usize, pred: &dyn Fn(usize) -> bool| {
+        (start..chars.len())
+            .find(|&j| pred(j))
+            .unwrap_or(chars.len())
+    };
+    let quoted = |quote: char, start: usize| {
+        let mut j = start;
+        while j < chars.len() {
+            match chars[j] {
+                '\\' => j += 2,
+                c if c == quote => return j + 1,
+                _ => j += 1,
+            }
+        }
+        chars.len()
+    };
+
+    match (chars[i], at(i + 1)) {
+        ('/', Some('/')) => Some(find_from(i, &|j| chars[j] == '\n')),
+        ('/', Some('*')) => {
+            // Rust block comments nest
+            let nests = language == Language::Rust;
+            let mut depth = 0;
+            let mut j = i;
+            while j < chars.len() {
+                match (chars[j], at(j + 1)) {
+                    ('/', Some('*')) if nests || depth == 0 => {
+                        depth += 1;
+                        j += 2;
+                    }
+                    ('*', Some('/')) => {
+                        depth -= 1;
+                        j += 2;
+                        if depth == 0 {
+                            return Some(j);
+                        }
+                    }
+                    _ => j += 1,
+                }
+            }
+            Some(chars.len())
+        }
+        ('/', _)
+            if matches!(language, Language::JavaScript | Language::TypeScript)
+                && starts_regex(chars, i) =>
+        {
+            regex_literal_end(chars, i)
+        }
+        ('"', _) => Some(quoted('"', i + 1)),
+        ('`', _) => Some(quoted('`', i + 1)),
+        ('\'', _) => match language {
+            // Character literals; anything else is a lifetime or label
+            Language::Rust | Language::Go => match (at(i + 1), at(i + 2)) {
+                (Some('\\'), _) => Some(quoted('\'', i + 1)),
+                (Some(_), Some('\'')) => Some(i + 3),
+                _ => None,
+            },
+            _ => Some(quoted('\'', i + 1)),
+        },
+        ('r', _) if language == Language::Rust => {
+            // Raw strings: r"..." and r#"..."#
+            let preceded_by_ident =
+                i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+            let hashes = (i + 1..chars.len())
+                .take_while(|&j| chars[j] == '#')
+                .count();
+            if preceded_by_ident || at(i + 1 + hashes) != Some('"') {
+                return None;
+            }
+            let mut j = i + 2 + hashes;
+            while j < chars.len() {
+                if chars[j] == '"' && (1..=hashes).all(|k| at(j + k) == Some('#')) {
+                    return Some(j + 1 + hashes);
+                }
+                j += 1;
+            }
+            Some(chars.len())
+        }
+        _ => None,
+    }
+}
+
+/// Whether the `/` at `chars[i]` starts a JavaScript regular expression
+/// literal rather than a division, judged by the token before it
+fn starts_regex(chars: &[char], i: usize) -> bool {
+    let Some(last) = chars[..i].iter().rposition(|c| !c.is_whitespace()) else {
+        return true;
+    };
+    let c = chars[last];
+    if "(,=:[!&|?{};+-*%<>~^".contains(c) {
+        return true;
+    }
+    let word_start = chars[..=last]
+        .iter()
+        .rposition(|c| !(c.is_alphanumeric() || *c == '_' || *c == '$'))
+        .map_or(0, |p| p + 1);
+    let word: String = chars[word_start..=last].iter().collect();
+    matches!(
+        word.as_str(),
+        "return"
+            | "typeof"
+            | "instanceof"
+            | "in"
+            | "of"
+            | "new"
+            | "delete"
+            | "void"
+            | "throw"
+            | "case"
+            | "do"
+            | "else"
+            | "yield"
+            | "await"
+    )
+}
+
+/// Index just past the regular expression literal (with its flags) at
+/// `chars[i]`, or None if the line ends first
+fn regex_literal_end(chars: &[char], i: usize) -> Option<usize> {
+    let mut in_class = false;
+    let mut j = i + 1;
+    while j < chars.len() {
+        match chars[j] {
+            '\n' => return None,
+            '\\' => j += 1,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => {
+                j += 1;
+                while chars.get(j).is_some_and(|c| c.is_alphabetic()) {
+                    j += 1;
+                }
+                return Some(j);
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+    None
+}
+
+/// Index just past the brace matching the one at `open`
+pub(crate) fn matching_brace(chars: &[char], open: usize, language: Language) -> usize {
+    let mut depth = 0;
+    let mut i = open;
+    while i < chars.len() {
+        if let Some(end) = skip_literal(chars, i, language) {
+            i = end;
+            continue;
+        }
+        match chars[i] {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// Whether the code preceding a `{` declares a function whose body should be collapsed
+fn opens_function_body(header: &str, language: Language) -> bool {
+    static RUST_FN: OnceLock<Regex> = OnceLock::new();
+    static GO_FUNC: OnceLock<Regex> = OnceLock::new();
+    static JS_FUNCTION: OnceLock<Regex> = OnceLock::new();
+    static JS_METHOD: OnceLock<Regex> = OnceLock::new();
+
+    let header = header.split_whitespace().collect::<Vec<_>>().join(" ");
+    match language {
+        Language::Rust => regex(&RUST_FN, r"\bfn\b").is_match(&header),
+        Language::Go => regex(&GO_FUNC, r"\bfunc\b").is_match(&header),
+        _ => {
+            if header.ends_with("=>") || regex(&JS_FUNCTION, r"\bfunction\b").is_match(&header) {
+                return true;
+            }
+            let method = regex(
+                &JS_METHOD,
+                r"^(?:@[\w.]+(?:\(.*?\))?\s*)*(?:(?:public|private|protected|static|async|readonly|override|abstract|get|set|export|default)\s+)*\*?\s*([A-Za-z_$#][\w$]*)\s*(?:<.*>)?\s*\(.*\)\s*(?::.*)?$",
+            );
+            method.captures(&header).is_some_and(|captures| {
+                !matches!(
+                    &captures[1],
+                    "if" | "for" | "while" | "switch" | "catch" | "with" | "return"
+                )
+            })
+        }
+    }
+}
+
+/// Outline a brace-delimited language by collapsing function bodies
+fn outline_braces(content: &str, language: Language) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len() / 2);
+    // Code since the last statement or block boundary, excluding comments
+    let mut header = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(end) = skip_literal(&chars, i, language) {
+            let literal: String = chars[i..end].iter().collect();
+            if !matches!(
+                (chars[i], chars.get(i + 1)),
+                ('/', Some('/')) | ('/', Some('*'))
+            ) {
+                header.push_str(&literal);
+            }
+            out.push_str(&literal);
+            i = end;
+            continue;
+        }
+        match chars[i] {
+            '{' if opens_function_body(&header, language) => {
+                out.push_str("{ ... }");
+                header.clear();
+                i = matching_brace(&chars, i, language);
+                continue;
+            }
+            c @ ('{' | '}' | ';') => {
+                out.push(c);
+                header.clear();
+            }
+            c => {
+                out.push(c);
+                header.push(c);
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Outline Python by replacing function bodies with `...`, keeping docstrings
+fn outline_python(content: &str) -> String {
+    static DEF: OnceLock<Regex> = OnceLock::new();
+    let def = regex(&DEF, r"^\s*(?:async\s+)?def\s");
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if !def.is_match(line) {
+            out.push(line.to_string());
+            i += 1;
+            continue;
+        }
+
+        // The signature may span lines until the closing colon
+        let def_indent = indent_of(line);
+        let mut depth = 0_i32;
+        loop {
+            let current = lines[i];
+            out.push(current.to_string());
+            depth += current.matches(['(', '[', '{']).count() as i32;
+            depth -= current.matches([')', ']', '}']).count() as i32;
+            i += 1;
+            if depth <= 0 || i >= lines.len() {
+                break;
+            }
+        }
+
+        // Single-line bodies such as `def f(): return 1` are kept as written
+        let body_start = i;
+        let body_end = (i..lines.len())
+            .find(|&j| !lines[j].trim().is_empty() && indent_of(lines[j]) <= def_indent)
+            .unwrap_or(lines.len());
+        let Some(first) = (body_start..body_end).find(|&j| !lines[j].trim().is_empty()) else {
+            continue;
+        };
+        let body_indent = " ".repeat(indent_of(lines[first]));
+
+        // Keep the docstring, if any
+        let trimmed = lines[first].trim_start();
+        let mut kept_until = first;
+        if let Some(quote) = ["\"\"\"", "'''"]
+            .into_iter()
+            .find(|q| trimmed.starts_with(q))
+        {
+            let rest = &trimmed[3..];
+            kept_until = if rest.contains(quote) {
+                first
+            } else {
+                (first + 1..body_end)
+                    .find(|&j| lines[j].contains(quote))
+                    .unwrap_or(body_end - 1)
+            };
+            out.extend(lines[first..=kept_until].iter().map(|l| l.to_string()));
+            kept_until += 1;
+        }
+        if kept_until < body_end {
+            out.push(format!("{}...", body_indent));
+        }
+
+        // Keep blank lines separating this function from the next declaration
+        i = body_end;
+        while i > kept_until && lines[i - 1].trim().is_empty() {
+            i -= 1;
+        }
+    }
+
+    let mut result = out.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
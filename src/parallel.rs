@@ -1,5 +1,13 @@
+#[cfg(feature = "sqlite")]
+use crate::sqlite;
 use crate::{
+    binary,
+    category::{categorize_notebook, is_generated_file, FileCategory},
+    data,
+    documents::{self, DocumentFormat},
+    lockfile,
     models::{InputConfig, OutputConfig, ProcessedFile, ProcessingConfig},
+    notebook,
     pipeline::ProcessingContext,
 };
 use anyhow::{anyhow, Result};
@@ -102,18 +110,7 @@ impl ParallelFileProcessor {
         base_path: &Path,
         _expanded_paths: &[std::path::PathBuf],
     ) -> std::path::PathBuf {
-        let path_str = base_path.to_string_lossy();
-
-        if path_str.contains('*') || path_str.contains('?') {
-            // For glob patterns, use current directory to ensure unique paths across different sources
-            std::env::current_dir().unwrap_or_else(|_| Path::new(".").to_path_buf())
-        } else if base_path.is_file() {
-            // For single files, use the parent directory
-            base_path.parent().unwrap_or(Path::new(".")).to_path_buf()
-        } else {
-            // For directories, use the directory itself
-            base_path.to_path_buf()
-        }
+        base_dir_for_input(base_path)
     }
 
     /// Process a single file
@@ -129,13 +126,22 @@ impl ParallelFileProcessor {
         // Read and process file content
         match self.context.file_system.read_file(file_path) {
             Ok(content) => {
-                if inspect(&content) == ContentType::BINARY {
+                let Some(content) = self.extract_document(&rel_path, content) else {
+                    return Ok(Vec::new());
+                };
+                #[cfg(feature = "sqlite")]
+                let Some(content) = self.describe_sqlite(file_path, &rel_path, content) else {
+                    return Ok(Vec::new());
+                };
+                let Some(content) = self.binary_placeholder(&rel_path, content) else {
                     debug!("Skipping binary file: {rel_path}");
-                    Ok(Vec::new())
-                } else {
-                    let processed_file = self.create_processed_file(&rel_path, &content)?;
-                    Ok(vec![processed_file])
-                }
+                    return Ok(Vec::new());
+                };
+                let content = self.summarize_lockfile(file_path, &rel_path, content);
+                let content = self.render_notebook(&rel_path, content);
+                let content = self.summarize_data(&rel_path, content);
+                let processed_file = self.create_processed_file(&rel_path, &content)?;
+                Ok(vec![processed_file])
             }
             Err(e) => {
                 debug!("Failed to read {rel_path}: {e}");
@@ -223,13 +229,31 @@ impl ParallelFileProcessor {
     ) -> Result<ProcessedFile> {
         // Read file content
         let content = self.context.file_system.read_file(file_path)?;
-
-        if inspect(&content) == ContentType::BINARY {
-            return Err(anyhow!("Binary file: {}", rel_path));
+        let content = self
+            .extract_document(rel_path, content)
+            .ok_or_else(|| anyhow!("Unreadable document: {}", rel_path))?;
+        #[cfg(feature = "sqlite")]
+        let content = self
+            .describe_sqlite(file_path, rel_path, content)
+            .ok_or_else(|| anyhow!("Unreadable database: {}", rel_path))?;
+
+        let content = self
+            .binary_placeholder(rel_path, content)
+            .ok_or_else(|| anyhow!("Binary file: {}", rel_path))?;
+
+        let content = self.summarize_lockfile(file_path, rel_path, content);
+        let content = self.render_notebook(rel_path, content);
+        let content = self.summarize_data(rel_path, content);
+
+        if !self.context.processing_config.include_generated
+            && is_generated_file(rel_path, &content)
+        {
+            debug!("Skipping generated file: {rel_path}");
+            return Err(anyhow!("Generated file: {}", rel_path));
         }
 
         // Calculate priority with category
-        let (priority, category) = self.calculate_priority_with_category(rel_path);
+        let (priority, category) = self.calculate_priority_with_category(rel_path, &content);
 
         // Get thread-safe file index
         let file_index = self.get_next_file_index(priority);
@@ -243,6 +267,120 @@ impl ParallelFileProcessor {
         ))
     }
 
+    /// Replace the content of a lockfile with a summary of its packages, if
+    /// enabled. The manifest next to the lockfile is read to tell direct
+    /// from transitive dependencies where the lockfile doesn't record them.
+    fn summarize_lockfile(&self, file_path: &Path, rel_path: &str, content: Vec<u8>) -> Vec<u8> {
+        if !self.context.processing_config.summarize_lockfiles || !lockfile::is_lockfile(rel_path) {
+            return content;
+        }
+        let manifest = lockfile::manifest_name(rel_path)
+            .and_then(|name| {
+                self.context
+                    .file_system
+                    .read_file(&file_path.with_file_name(name))
+                    .ok()
+            })
+            .map(|manifest| String::from_utf8_lossy(&manifest).to_string());
+        match lockfile::summarize_lockfile(
+            rel_path,
+            &String::from_utf8_lossy(&content),
+            manifest.as_deref(),
+        ) {
+            Some(summary) => summary.into_bytes(),
+            None => {
+                debug!("Could not parse lockfile {rel_path}, keeping it as is");
+                content
+            }
+        }
+    }
+
+    /// Replace a PDF, DOCX or ODT document with its text, if enabled.
+    /// Returns `None` for documents whose text can't be extracted.
+    fn extract_document(&self, rel_path: &str, content: Vec<u8>) -> Option<Vec<u8>> {
+        let config = &self.context.processing_config;
+        if !config.extract_documents || DocumentFormat::from_path(rel_path).is_none() {
+            return Some(content);
+        }
+        let text = documents::extract_text(rel_path, &content, config.document_max_size);
+        if text.is_none() {
+            debug!("Skipping document without extractable text: {rel_path}");
+        }
+        text.map(String::into_bytes)
+    }
+
+    /// Replace a Git LFS pointer with a one-line label, and a binary file
+    /// with a one-line placeholder if enabled. Returns `None` for binary
+    /// files otherwise.
+    fn binary_placeholder(&self, rel_path: &str, content: Vec<u8>) -> Option<Vec<u8>> {
+        if inspect(&content) == ContentType::BINARY {
+            let enabled = self.context.processing_config.binary_placeholders;
+            return enabled.then(|| binary::describe_binary(rel_path, &content).into_bytes());
+        }
+        match binary::parse_lfs_pointer(&content) {
+            Some(pointer) => Some(binary::describe_lfs_pointer(rel_path, &pointer).into_bytes()),
+            None => Some(content),
+        }
+    }
+
+    /// Replace a SQLite database with its schema, row counts and first
+    /// rows, if enabled. Returns `None` for databases that can't be opened.
+    #[cfg(feature = "sqlite")]
+    fn describe_sqlite(
+        &self,
+        file_path: &Path,
+        rel_path: &str,
+        content: Vec<u8>,
+    ) -> Option<Vec<u8>> {
+        let config = &self.context.processing_config;
+        if !config.sqlite_schema || !sqlite::is_sqlite_database(rel_path, &content) {
+            return Some(content);
+        }
+        match sqlite::describe_database(file_path, config.sqlite_rows) {
+            Ok(description) => Some(description.into_bytes()),
+            Err(e) => {
+                debug!("Skipping unreadable database {rel_path}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Render a Jupyter notebook as its cells, without the JSON structure
+    /// and rich outputs
+    fn render_notebook(&self, rel_path: &str, content: Vec<u8>) -> Vec<u8> {
+        if !notebook::is_notebook(rel_path) {
+            return content;
+        }
+        match notebook::render_notebook(
+            &String::from_utf8_lossy(&content),
+            self.context.processing_config.notebook_outputs,
+        ) {
+            Some(rendered) => rendered.into_bytes(),
+            None => {
+                debug!("Could not parse notebook {rel_path}, keeping it as is");
+                content
+            }
+        }
+    }
+
+    /// Replace the content of a data file larger than the configured size
+    /// with its schema and first records, if enabled
+    fn summarize_data(&self, rel_path: &str, content: Vec<u8>) -> Vec<u8> {
+        let config = &self.context.processing_config;
+        match config.summarize_data_above {
+            Some(limit) if content.len() > limit => {}
+            _ => return content,
+        }
+        match data::summarize_data(
+            rel_path,
+            &String::from_utf8_lossy(&content),
+            config.data_sample,
+        ) {
+            Some(summary) => summary.into_bytes(),
+            None => content,
+        }
+    }
+
     /// Calculate priority for a file (legacy method for backward compatibility)
     #[allow(dead_code)]
     fn calculate_priority(&self, rel_path: &str) -> i32 {
@@ -270,19 +408,32 @@ impl ParallelFileProcessor {
         priority
     }
 
-    /// Calculate priority for a file including category-based offset
+    /// Calculate priority for a file including category-based offset.
+    /// Generated files get the `generated` offset instead of the one of
+    /// their path-based category.
     fn calculate_priority_with_category(
         &self,
         rel_path: &str,
-    ) -> (i32, crate::category::FileCategory) {
+        content: &[u8],
+    ) -> (i32, FileCategory) {
         use crate::priority::get_file_priority_with_category;
 
+        let weights = &self.context.processing_config.category_weights;
+
         // Get base priority from rules and category
-        let (mut priority, category) = get_file_priority_with_category(
+        let (mut priority, mut category) = get_file_priority_with_category(
             rel_path,
             &self.context.processing_config.priority_rules,
-            &self.context.processing_config.category_weights,
+            weights,
         );
+        if is_generated_file(rel_path, content) {
+            priority += weights.get_offset(FileCategory::Generated) - weights.get_offset(category);
+            category = FileCategory::Generated;
+        } else if category == FileCategory::Source && notebook::is_notebook(rel_path) {
+            let notebook_category = categorize_notebook(&String::from_utf8_lossy(content));
+            priority += weights.get_offset(notebook_category) - weights.get_offset(category);
+            category = notebook_category;
+        }
 
         // Apply git boost if available
         if let Some(commit_time) = self.context.repository_info.commit_times.get(rel_path) {
@@ -340,12 +491,19 @@ impl ParallelFileProcessor {
             .iter()
             .any(|pattern| pattern.matches(&path_str));
 
-        // Check binary extensions
-        let is_binary = file_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| self.context.input_config.binary_extensions.contains(ext))
-            .unwrap_or(false);
+        // Check binary extensions, except for documents whose text is extracted,
+        // databases whose schema is described, and files that get placeholders
+        let config = &self.context.processing_config;
+        let exempt = config.binary_placeholders
+            || (config.extract_documents && DocumentFormat::from_path(&path_str).is_some());
+        #[cfg(feature = "sqlite")]
+        let exempt = exempt || (config.sqlite_schema && sqlite::has_sqlite_extension(&path_str));
+        let is_binary = !exempt
+            && file_path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| self.context.input_config.binary_extensions.contains(ext))
+                .unwrap_or(false);
 
         ignored_by_pattern || is_binary
     }
@@ -370,7 +528,7 @@ impl ParallelFileProcessor {
 
     /// Create a processed file with proper metadata
     fn create_processed_file(&self, rel_path: &str, content: &[u8]) -> Result<ProcessedFile> {
-        let (priority, category) = self.calculate_priority_with_category(rel_path);
+        let (priority, category) = self.calculate_priority_with_category(rel_path, content);
         let file_index = self.get_next_file_index(priority);
 
         Ok(ProcessedFile::new_with_category(
@@ -393,6 +551,22 @@ impl ParallelFileProcessor {
     }
 }
 
+/// Determine the directory that relative paths of an input path are computed from
+pub fn base_dir_for_input(base_path: &Path) -> std::path::PathBuf {
+    let path_str = base_path.to_string_lossy();
+
+    if path_str.contains('*') || path_str.contains('?') {
+        // For glob patterns, use current directory to ensure unique paths across different sources
+        std::env::current_dir().unwrap_or_else(|_| Path::new(".").to_path_buf())
+    } else if base_path.is_file() {
+        // For single files, use the parent directory
+        base_path.parent().unwrap_or(Path::new(".")).to_path_buf()
+    } else {
+        // For directories, use the directory itself
+        base_path.to_path_buf()
+    }
+}
+
 /// Create a relative, slash-normalized path
 pub fn normalize_path(path: &Path, base: &Path) -> String {
     path.strip_prefix(base)
@@ -427,6 +601,18 @@ pub fn process_files_parallel(
         ProcessingConfig {
             priority_rules: config.priority_rules.clone(),
             category_weights: config.category_weights.clone().unwrap_or_default(),
+            include_generated: config.include_generated,
+            summarize_lockfiles: config.summarize_lockfiles,
+            summarize_data_above: config.summarize_data_limit()?,
+            data_sample: config.data_sample,
+            notebook_outputs: config.notebook_outputs,
+            extract_documents: config.extract_documents,
+            document_max_size: config.document_size_limit()?,
+            #[cfg(feature = "sqlite")]
+            sqlite_schema: config.sqlite_schema,
+            #[cfg(feature = "sqlite")]
+            sqlite_rows: config.sqlite_rows,
+            binary_placeholders: config.binary_placeholders,
             debug: config.debug,
             parallel: true,
             max_threads: None,
@@ -1,7 +1,10 @@
 use git2;
 use regex;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
 use tracing::debug;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -47,6 +50,39 @@ pub fn get_file_priority_with_category(
     (total_priority, category)
 }
 
+/// Relevance boost of each file for a search query, aligned with `files`.
+/// Files are scored with BM25 over their path and content, then scaled so
+/// the best match gets `max_boost`. Files that don't match get 0.
+pub fn compute_relevance_boost(
+    files: &[crate::models::ProcessedFile],
+    query: &str,
+    max_boost: i32,
+) -> Vec<i32> {
+    let index = crate::relevance::Bm25Index::new(
+        files
+            .iter()
+            .map(|f| (f.rel_path.as_str(), f.content.as_str())),
+    );
+    let scores = index.score(query);
+    let best = scores.iter().cloned().fold(0.0_f64, f64::max);
+    if best <= 0.0 {
+        return vec![0; files.len()];
+    }
+
+    files
+        .iter()
+        .zip(scores)
+        .map(|(file, score)| {
+            let boost = (score / best * max_boost as f64).round() as i32;
+            debug!(
+                "File: {} | BM25: {:.3} | Query boost: {}",
+                file.rel_path, score, boost
+            );
+            boost
+        })
+        .collect()
+}
+
 /// Rank-based approach to compute how "recent" each file is (0=oldest, 1=newest).
 /// Then scale it to a user-defined or default max boost.
 pub fn compute_recentness_boost(
@@ -175,3 +211,108 @@ pub fn get_recent_commit_times_git2(
 
     Some(commit_times)
 }
+
+/// Parse a `--since` value into a Unix timestamp. Accepts a date (`YYYY-MM-DD`,
+/// midnight UTC) or an age relative to `now` such as `30d`, `2w`, `3mo` or `1y`.
+pub fn parse_since(value: &str, now: i64) -> anyhow::Result<i64> {
+    const DAY: i64 = 24 * 60 * 60;
+    let value = value.trim();
+    let invalid = || {
+        anyhow::anyhow!(
+            "since: Invalid value '{}', expected YYYY-MM-DD or an age like 30d, 2w, 3mo, 1y",
+            value
+        )
+    };
+
+    let parts: Vec<&str> = value.split('-').collect();
+    if parts.len() == 3 {
+        let year = parts[0].parse::<i32>().map_err(|_| invalid())?;
+        let month = parts[1].parse::<u8>().map_err(|_| invalid())?;
+        let day = parts[2].parse::<u8>().map_err(|_| invalid())?;
+        let month = time::Month::try_from(month).map_err(|_| invalid())?;
+        let date = time::Date::from_calendar_date(year, month, day).map_err(|_| invalid())?;
+        return Ok(date.midnight().assume_utc().unix_timestamp());
+    }
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        "d" => DAY,
+        "w" => 7 * DAY,
+        "mo" => 30 * DAY,
+        "y" => 365 * DAY,
+        _ => return Err(invalid()),
+    };
+    amount
+        .checked_mul(unit_secs)
+        .and_then(|age| now.checked_sub(age))
+        .ok_or_else(|| anyhow::anyhow!("since: Age '{}' is out of range", value))
+}
+
+/// Find every file touched by a commit whose message matches `pattern`.
+/// Returns paths relative to the repository root. Commits older than `since`
+/// (a Unix timestamp) are skipped. Returns None outside a Git repository.
+pub fn get_files_touched_by_matching_commits(
+    repo: &git2::Repository,
+    pattern: &regex::Regex,
+    since: Option<i64>,
+) -> Option<HashSet<String>> {
+    let mut revwalk = repo.revwalk().ok()?;
+    if let Err(e) = revwalk.push_head() {
+        debug!("Unable to push HEAD to revwalk: {:?}", e);
+        return None;
+    }
+    revwalk
+        .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+        .ok()?;
+
+    let mut touched = HashSet::new();
+    for oid in revwalk.flatten() {
+        let commit = match repo.find_commit(oid) {
+            Ok(commit) => commit,
+            Err(e) => {
+                debug!("Failed to find commit for OID {:?}: {:?}", oid, e);
+                continue;
+            }
+        };
+        // Topological order isn't strictly by time across merged branches,
+        // so newer commits may still follow an older one
+        if since.is_some_and(|since| commit.time().seconds() < since) {
+            continue;
+        }
+        if !commit
+            .message()
+            .is_some_and(|message| pattern.is_match(message))
+        {
+            continue;
+        }
+
+        let tree = match commit.tree() {
+            Ok(tree) => tree,
+            Err(e) => {
+                debug!("Failed to get tree for commit {:?}: {:?}", oid, e);
+                continue;
+            }
+        };
+        let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+        let diff = match repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) {
+            Ok(diff) => diff,
+            Err(e) => {
+                debug!("Failed to diff commit {:?}: {:?}", oid, e);
+                continue;
+            }
+        };
+        for delta in diff.deltas() {
+            for file in [delta.old_file(), delta.new_file()] {
+                if let Some(path) = file.path().and_then(|p| p.to_str()) {
+                    touched.insert(path.to_string());
+                }
+            }
+        }
+    }
+
+    Some(touched)
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+/// BM25 document length normalization
+const B: f64 = 0.75;
+/// Path tokens count this many times, since file names are strong signals
+const PATH_WEIGHT: usize = 2;
+
+/// Split text into lowercase search tokens. Identifiers are split on
+/// camelCase, PascalCase, snake_case and kebab-case boundaries, and compound
+/// identifiers are also kept whole, so `refreshToken` yields `refresh`,
+/// `token` and `refreshtoken`.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        let parts: Vec<String> = word
+            .split('_')
+            .flat_map(split_camel_case)
+            .map(|part| part.to_lowercase())
+            .collect();
+        if parts.len() > 1 {
+            tokens.push(parts.concat());
+        }
+        tokens.extend(parts);
+    }
+    tokens
+}
+
+/// Split an identifier at lower→upper and acronym→word boundaries
+/// (`HTTPServerError` → `HTTP`, `Server`, `Error`)
+fn split_camel_case(word: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (index, c) = chars[i];
+        let prev = chars[i - 1].1;
+        let next_is_lower = chars.get(i + 1).is_some_and(|(_, n)| n.is_lowercase());
+        let boundary = (prev.is_lowercase() && c.is_uppercase())
+            || (prev.is_uppercase() && c.is_uppercase() && next_is_lower)
+            || (prev.is_alphabetic() && c.is_numeric())
+            || (prev.is_numeric() && c.is_alphabetic());
+        if boundary {
+            parts.push(&word[start..index]);
+            start = index;
+        }
+    }
+    if start < word.len() {
+        parts.push(&word[start..]);
+    }
+    parts
+}
+
+/// In-memory BM25 index over documents made of a path and its content
+pub struct Bm25Index {
+    term_frequencies: Vec<HashMap<String, usize>>,
+    lengths: Vec<usize>,
+    document_frequencies: HashMap<String, usize>,
+    average_length: f64,
+}
+
+impl Bm25Index {
+    /// Index `(path, content)` documents
+    pub fn new<'a>(documents: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut term_frequencies = Vec::new();
+        let mut lengths = Vec::new();
+        let mut document_frequencies: HashMap<String, usize> = HashMap::new();
+
+        for (path, content) in documents {
+            let mut frequencies: HashMap<String, usize> = HashMap::new();
+            let mut length = 0;
+            for token in tokenize(path) {
+                *frequencies.entry(token).or_insert(0) += PATH_WEIGHT;
+                length += PATH_WEIGHT;
+            }
+            for token in tokenize(content) {
+                *frequencies.entry(token).or_insert(0) += 1;
+                length += 1;
+            }
+            for term in frequencies.keys() {
+                *document_frequencies.entry(term.clone()).or_insert(0) += 1;
+            }
+            term_frequencies.push(frequencies);
+            lengths.push(length);
+        }
+
+        let average_length = if lengths.is_empty() {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+        };
+        Self {
+            term_frequencies,
+            lengths,
+            document_frequencies,
+            average_length,
+        }
+    }
+
+    /// BM25 score of every document for `query`, in indexing order
+    pub fn score(&self, query: &str) -> Vec<f64> {
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+
+        let count = self.term_frequencies.len() as f64;
+        let idf: Vec<(&str, f64)> = terms
+            .iter()
+            .filter_map(|term| {
+                let df = *self.document_frequencies.get(term)? as f64;
+                Some((term.as_str(), ((count - df + 0.5) / (df + 0.5) + 1.0).ln()))
+            })
+            .collect();
+
+        self.term_frequencies
+            .iter()
+            .zip(&self.lengths)
+            .map(|(frequencies, &length)| {
+                let norm = if self.average_length > 0.0 {
+                    1.0 - B + B * length as f64 / self.average_length
+                } else {
+                    1.0
+                };
+                idf.iter()
+                    .map(|(term, idf)| {
+                        let tf = *frequencies.get(*term).unwrap_or(&0) as f64;
+                        idf * tf * (K1 + 1.0) / (tf + K1 * norm)
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    models::ProcessedFile,
+    symbols::{top_level_symbols, TopLevelSymbol},
+};
+use rayon::prelude::*;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Generate a repository map listing each file with its top-level symbols.
+///
+/// Symbols are ranked by how often their name is referenced in the other
+/// files, and the most referenced ones are kept until `budget` (measured
+/// with `measure`, in bytes or tokens) is used up. Files are listed in the
+/// order of their best ranked symbol; files without a listed symbol follow
+/// by path while room remains. Files in `excluded` are marked as not
+/// included in the output.
+pub fn generate_repo_map(
+    included: &[&ProcessedFile],
+    excluded: &[&ProcessedFile],
+    budget: usize,
+    measure: impl Fn(&str) -> usize,
+) -> String {
+    const TITLE: &str = "Repository map:\n";
+
+    let files: Vec<(&ProcessedFile, bool)> = included
+        .iter()
+        .map(|f| (*f, false))
+        .chain(excluded.iter().map(|f| (*f, true)))
+        .collect();
+    // The title and the blank line closing the map
+    let mut used = measure(TITLE) + measure("\n");
+    if files.is_empty() || used > budget {
+        return String::new();
+    }
+
+    let symbols: Vec<Vec<TopLevelSymbol>> = files
+        .par_iter()
+        .map(|(file, _)| top_level_symbols(&file.rel_path, &file.content))
+        .collect();
+    let references = count_references(&files, &symbols);
+
+    // Rank every (file, symbol) pair by reference count
+    let mut ranked: Vec<(usize, usize, usize)> = symbols
+        .iter()
+        .enumerate()
+        .flat_map(|(file_index, file_symbols)| {
+            let references = &references[file_index];
+            (0..file_symbols.len()).map(move |i| (file_index, i, references[i]))
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.2.cmp(&a.2)
+            .then_with(|| files[a.0].0.rel_path.cmp(&files[b.0].0.rel_path))
+            .then_with(|| a.1.cmp(&b.1))
+    });
+
+    let file_line = |file_index: usize| {
+        let (file, is_excluded) = files[file_index];
+        if is_excluded {
+            format!("{} (not included)\n", file.rel_path)
+        } else {
+            format!("{}\n", file.rel_path)
+        }
+    };
+
+    // Greedily keep the most referenced symbols, remembering the order in
+    // which files first appear
+    let mut file_order: Vec<usize> = Vec::new();
+    let mut kept: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (file_index, symbol_index, _) in ranked {
+        let line = format!("  {}\n", symbols[file_index][symbol_index].signature);
+        let mut cost = measure(&line);
+        let new_file = !kept.contains_key(&file_index);
+        if new_file {
+            cost += measure(&file_line(file_index));
+        }
+        if used + cost > budget {
+            continue;
+        }
+        used += cost;
+        if new_file {
+            file_order.push(file_index);
+        }
+        kept.entry(file_index).or_default().push(symbol_index);
+    }
+
+    // Files without listed symbols, by path
+    let mut remaining: Vec<usize> = (0..files.len()).filter(|i| !kept.contains_key(i)).collect();
+    remaining.sort_by(|a, b| files[*a].0.rel_path.cmp(&files[*b].0.rel_path));
+    for file_index in remaining {
+        let cost = measure(&file_line(file_index));
+        if used + cost <= budget {
+            used += cost;
+            file_order.push(file_index);
+        }
+    }
+
+    let mut output = String::from(TITLE);
+    for file_index in file_order {
+        output.push_str(&file_line(file_index));
+        for &symbol_index in kept.get(&file_index).into_iter().flatten() {
+            output.push_str("  ");
+            output.push_str(&symbols[file_index][symbol_index].signature);
+            output.push('\n');
+        }
+    }
+    output.push('\n');
+    output
+}
+
+/// Number of references to each symbol from files other than the one
+/// declaring it, counted as whole-word occurrences of the symbol name
+fn count_references(
+    files: &[(&ProcessedFile, bool)],
+    symbols: &[Vec<TopLevelSymbol>],
+) -> Vec<Vec<usize>> {
+    static IDENTIFIER: OnceLock<Regex> = OnceLock::new();
+    let identifier =
+        IDENTIFIER.get_or_init(|| Regex::new(r"[A-Za-z_$][\w$]*").expect("valid regex"));
+
+    let names: HashSet<&str> = symbols
+        .iter()
+        .flatten()
+        .map(|symbol| symbol.name.as_str())
+        .collect();
+    // Occurrences of each symbol name, per file
+    let per_file: Vec<HashMap<&str, usize>> = files
+        .par_iter()
+        .map(|(file, _)| {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for word in identifier.find_iter(&file.content) {
+                if let Some(name) = names.get(word.as_str()) {
+                    *counts.entry(name).or_default() += 1;
+                }
+            }
+            counts
+        })
+        .collect();
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for counts in &per_file {
+        for (name, count) in counts {
+            *totals.entry(name).or_default() += count;
+        }
+    }
+
+    symbols
+        .iter()
+        .enumerate()
+        .map(|(file_index, file_symbols)| {
+            file_symbols
+                .iter()
+                .map(|symbol| {
+                    let name = symbol.name.as_str();
+                    let total = totals.get(name).copied().unwrap_or(0);
+                    let own = per_file[file_index].get(name).copied().unwrap_or(0);
+                    total - own
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,126 @@
+use crate::{
+    models::{LineWindow, ProcessedFile, Selection},
+    snippets::reduce_to_windows,
+    symbols::find_symbol,
+};
+use std::path::Path;
+
+/// Split an input path into the path on disk and an optional selection
+/// within it, e.g. `src/lib.rs#concat_files`, `src/lib.rs:10-40` or
+/// `src/lib.rs:10+30`. Paths that exist as given are never split, so file
+/// names containing `#` or `:` keep working.
+pub fn parse_input_path(path_str: &str) -> (&str, Option<Selection>) {
+    if Path::new(path_str).exists() {
+        return (path_str, None);
+    }
+    if let Some((file, symbol)) = path_str.rsplit_once('#') {
+        if !symbol.is_empty() && Path::new(file).is_file() {
+            return (file, Some(Selection::Symbol(symbol.to_string())));
+        }
+    }
+    if let Some((file, range)) = path_str.rsplit_once(':') {
+        if Path::new(file).is_file() {
+            if let Some((start, end)) = parse_line_range(range) {
+                return (file, Some(Selection::Lines { start, end }));
+            }
+        }
+    }
+    (path_str, None)
+}
+
+/// Parse `START-END` or `START+COUNT` into an inclusive 1-based range
+pub fn parse_line_range(range: &str) -> Option<(usize, usize)> {
+    let (start, end) = if let Some((start, end)) = range.split_once('-') {
+        (start.trim().parse().ok()?, end.trim().parse().ok()?)
+    } else {
+        let (start, count) = range.split_once('+')?;
+        let start: usize = start.trim().parse().ok()?;
+        let count: usize = count.trim().parse().ok()?;
+        (start, start.checked_add(count)?.checked_sub(1)?)
+    };
+    (start >= 1 && end >= start).then_some((start, end))
+}
+
+/// Whether an input path looks like `file:RANGE` for an existing file but
+/// the range is malformed, e.g. `src/lib.rs:40-10`
+pub fn has_invalid_line_range(path_str: &str) -> bool {
+    if Path::new(path_str).exists() {
+        return false;
+    }
+    path_str.rsplit_once(':').is_some_and(|(file, range)| {
+        Path::new(file).is_file()
+            && range.starts_with(|c: char| c.is_ascii_digit())
+            && parse_line_range(range).is_none()
+    })
+}
+
+/// Reduce `files` to the selected part. Files where the selection cannot be
+/// found are dropped with a warning.
+pub fn apply_selection(files: &mut Vec<ProcessedFile>, selection: &Selection) {
+    files.retain_mut(|file| {
+        let window = match selection {
+            Selection::Symbol(name) => find_symbol(&file.rel_path, &file.content, name),
+            Selection::Lines { start, end } => {
+                let total = file.content.lines().count();
+                (*start <= total).then(|| LineWindow {
+                    start: *start,
+                    end: (*end).min(total),
+                })
+            }
+        };
+        match window {
+            Some(window) => {
+                reduce_to_windows(file, &[window]);
+                file.selection = Some(selection.clone());
+                true
+            }
+            None => {
+                let reason = match selection {
+                    Selection::Symbol(_) => "not found in",
+                    Selection::Lines { .. } => "is outside",
+                };
+                eprintln!(
+                    "Warning: '{}' {} '{}'",
+                    selection.label(),
+                    reason,
+                    file.rel_path
+                );
+                false
+            }
+        }
+    });
+}
+
+impl Selection {
+    /// Short form of the selection, e.g. `#concat_files` or `:10-40`
+    pub fn label(&self) -> String {
+        match self {
+            Selection::Symbol(name) => format!("#{}", name),
+            Selection::Lines { start, end } => format!(":{}-{}", start, end),
+        }
+    }
+}
+
+/// File path as shown in headers: selected files and parts of split files
+/// show the original line range, and the item name for symbols, e.g.
+/// `src/lib.rs#concat_files (lines 157-277)` or `src/lib.rs (part 2/3, lines 400-820)`
+pub fn display_path(file: &ProcessedFile) -> String {
+    if let (Some(part), Some(first), Some(last)) =
+        (&file.part, file.windows.first(), file.windows.last())
+    {
+        return format!(
+            "{} (part {}/{}, lines {}-{})",
+            file.rel_path, part.index, part.count, first.start, last.end
+        );
+    }
+    match (&file.selection, file.windows.first(), file.windows.last()) {
+        (Some(Selection::Symbol(name)), Some(first), Some(last)) => format!(
+            "{}#{} (lines {}-{})",
+            file.rel_path, name, first.start, last.end
+        ),
+        (Some(Selection::Lines { .. }), Some(first), Some(last)) => {
+            format!("{} (lines {}-{})", file.rel_path, first.start, last.end)
+        }
+        _ => file.rel_path.clone(),
+    }
+}
@@ -0,0 +1,104 @@
+use crate::models::{LineWindow, ProcessedFile};
+use rayon::prelude::*;
+use regex::Regex;
+
+/// Line windows around every line of `content` matching `pattern`, with
+/// `context` lines on each side. Overlapping or adjacent windows are merged.
+pub fn grep_windows(content: &str, pattern: &Regex, context: usize) -> Vec<LineWindow> {
+    let total = content.lines().count();
+    let mut windows: Vec<LineWindow> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if !pattern.is_match(line) {
+            continue;
+        }
+        let line_number = index + 1;
+        let window = LineWindow {
+            start: line_number.saturating_sub(context).max(1),
+            end: (line_number + context).min(total),
+        };
+        match windows.last_mut() {
+            Some(last) if window.start <= last.end + 1 => last.end = last.end.max(window.end),
+            _ => windows.push(window),
+        }
+    }
+    windows
+}
+
+/// Reduce each file to the windows around lines matching `pattern`.
+/// Files without a match are dropped.
+pub fn grep_files(files: &mut Vec<ProcessedFile>, pattern: &Regex, context: usize) {
+    files.par_iter_mut().for_each(|file| {
+        let windows = grep_windows(&file.content, pattern, context);
+        reduce_to_windows(file, &windows);
+    });
+    files.retain(|file| !file.windows.is_empty());
+}
+
+/// Keep only the lines of `file` inside `windows`, which are numbered
+/// against the current content. If the file was already reduced, the kept
+/// lines are mapped back to their original line numbers.
+pub fn reduce_to_windows(file: &mut ProcessedFile, windows: &[LineWindow]) {
+    let original_numbers = original_line_numbers(file);
+    let lines: Vec<&str> = file.content.lines().collect();
+
+    let mut kept_lines = Vec::new();
+    let mut kept_blame = Vec::new();
+    let mut kept_windows: Vec<LineWindow> = Vec::new();
+    for window in windows {
+        for index in window.start.saturating_sub(1)..window.end.min(lines.len()) {
+            kept_lines.push(lines[index]);
+            if let Some(tag) = file.blame.get(index) {
+                kept_blame.push(tag.clone());
+            }
+            let original = original_numbers[index];
+            match kept_windows.last_mut() {
+                Some(last) if last.end + 1 == original => last.end = original,
+                _ => kept_windows.push(LineWindow {
+                    start: original,
+                    end: original,
+                }),
+            }
+        }
+    }
+
+    let mut content = kept_lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    file.replace_content(content);
+    file.blame = kept_blame;
+    file.windows = kept_windows;
+}
+
+/// Original line number of each line of the file's current content
+pub fn original_line_numbers(file: &ProcessedFile) -> Vec<usize> {
+    if file.windows.is_empty() {
+        return (1..=file.content.lines().count()).collect();
+    }
+    file.windows
+        .iter()
+        .flat_map(|window| window.start..=window.end)
+        .collect()
+}
+
+/// Render reduced content with original line numbers, separating
+/// non-contiguous windows with `...`
+pub fn format_windows(content: &str, windows: &[LineWindow]) -> String {
+    let last_line = windows.last().map(|w| w.end).unwrap_or(0);
+    let width = std::cmp::max(3, last_line.to_string().len());
+
+    let mut lines = content.lines();
+    let mut blocks = Vec::with_capacity(windows.len());
+    for window in windows {
+        let block = (window.start..=window.end)
+            .map_while(|number| {
+                lines
+                    .next()
+                    .map(|line| format!("{:width$} | {}", number, line, width = width))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        blocks.push(block);
+    }
+    blocks.join("\n...\n")
+}
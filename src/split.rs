@@ -0,0 +1,62 @@
+use crate::{
+    chunks::split_ranges,
+    count_tokens,
+    models::{FilePart, LineWindow, ProcessedFile},
+    snippets::reduce_to_windows,
+};
+use rayon::prelude::*;
+
+/// Split every complete file larger than `limit` (bytes, or tokens in token
+/// mode) into consecutive parts of at most `limit`, breaking at top-level
+/// items where possible and at blank lines otherwise. Each part keeps the
+/// file's priority and its original line numbers. Files already reduced to
+/// line windows are left as they are.
+pub fn split_large_files(
+    files: Vec<ProcessedFile>,
+    limit: usize,
+    token_mode: bool,
+) -> Vec<ProcessedFile> {
+    files
+        .into_par_iter()
+        .flat_map_iter(|file| {
+            let cost = |text: &str| {
+                if token_mode {
+                    count_tokens(text)
+                } else {
+                    text.len()
+                }
+            };
+            if !file.windows.is_empty() || cost(&file.content) <= limit {
+                return vec![file];
+            }
+
+            let lines: Vec<&str> = file.content.lines().collect();
+            let line_costs: Vec<usize> = lines.iter().map(|line| cost(line) + 1).collect();
+            let ranges = split_ranges(&file.rel_path, &file.content, &lines, &line_costs, limit, 0);
+            if ranges.len() < 2 {
+                return vec![file];
+            }
+
+            let count = ranges.len();
+            ranges
+                .into_iter()
+                .enumerate()
+                .map(|(index, (from, to))| {
+                    let mut part = file.clone();
+                    reduce_to_windows(
+                        &mut part,
+                        &[LineWindow {
+                            start: from + 1,
+                            end: to,
+                        }],
+                    );
+                    part.part = Some(FilePart {
+                        index: index + 1,
+                        count,
+                    });
+                    part
+                })
+                .collect()
+        })
+        .collect()
+}
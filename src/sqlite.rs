@@ -0,0 +1,138 @@
+use anyhow::Result;
+use rusqlite::{types::ValueRef, Connection, OpenFlags};
+use std::path::Path;
+
+/// Extensions of SQLite database files
+const SQLITE_EXTENSIONS: &[&str] = &["db", "sqlite", "sqlite3", "db3", "s3db", "sl3"];
+
+/// First bytes of every SQLite 3 database
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Characters kept of each text value in sample rows
+const MAX_VALUE: usize = 80;
+
+/// Whether `path` has the extension of a SQLite database
+pub fn has_sqlite_extension(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SQLITE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Whether a file with the given path and content is a SQLite database
+pub fn is_sqlite_database(path: &str, content: &[u8]) -> bool {
+    has_sqlite_extension(path) && content.starts_with(SQLITE_HEADER)
+}
+
+/// Describe a SQLite database as SQL: the statements creating its tables,
+/// indexes, views and triggers, each table followed by its row count and
+/// up to `sample_rows` rows as comments. The database is opened read-only
+/// and treated as immutable, so neither it nor its journal is touched.
+pub fn describe_database(path: &Path, sample_rows: usize) -> Result<String> {
+    let uri = format!("file:{}?immutable=1", uri_path(path));
+    let connection = Connection::open_with_flags(
+        uri,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI,
+    )?;
+
+    let mut statement = connection.prepare(
+        "SELECT type, name, sql FROM sqlite_master \
+         WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' \
+         ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'index' THEN 1 \
+         WHEN 'view' THEN 2 ELSE 3 END, rowid",
+    )?;
+    let entries = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let count = |kind: &str| entries.iter().filter(|(k, _, _)| k == kind).count();
+    let mut output = format!(
+        "-- SQLite database: {} tables, {} indexes, {} views, {} triggers\n",
+        count("table"),
+        count("index"),
+        count("view"),
+        count("trigger")
+    );
+    for (kind, name, sql) in &entries {
+        output.push('\n');
+        output.push_str(sql.trim_end_matches(';'));
+        output.push_str(";\n");
+        if kind == "table" {
+            describe_rows(&connection, name, sample_rows, &mut output);
+        }
+    }
+    Ok(output)
+}
+
+/// Append the row count and the first rows of a table as comments
+fn describe_rows(connection: &Connection, table: &str, sample_rows: usize, output: &mut String) {
+    let table = format!("\"{}\"", table.replace('"', "\"\""));
+    // Virtual tables whose module isn't available can't be read
+    let rows: Option<i64> = connection
+        .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })
+        .ok();
+    let Some(rows) = rows else {
+        output.push_str("-- rows: unknown\n");
+        return;
+    };
+    let sample = sample_rows.min(rows as usize);
+    if sample == 0 {
+        output.push_str(&format!("-- {} rows\n", rows));
+        return;
+    }
+
+    output.push_str(&format!("-- {} rows, first {}:\n", rows, sample));
+    let query = format!("SELECT * FROM {} LIMIT {}", table, sample);
+    let Ok(mut statement) = connection.prepare(&query) else {
+        return;
+    };
+    let columns = statement.column_count();
+    let Ok(mut result) = statement.query([]) else {
+        return;
+    };
+    while let Ok(Some(row)) = result.next() {
+        let values: Vec<String> = (0..columns)
+            .map(|i| row.get_ref(i).map(format_value).unwrap_or_default())
+            .collect();
+        output.push_str(&format!("--   ({})\n", values.join(", ")));
+    }
+}
+
+/// A value as a SQL literal, with long text shortened and blobs described
+fn format_value(value: ValueRef<'_>) -> String {
+    match value {
+        ValueRef::Null => "NULL".to_string(),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(text) => {
+            let text = String::from_utf8_lossy(text);
+            let mut shortened: String = text.chars().take(MAX_VALUE).collect();
+            if shortened.len() < text.len() {
+                shortened.push_str("...");
+            }
+            format!("'{}'", shortened.replace('\'', "''").replace('\n', "\\n"))
+        }
+        ValueRef::Blob(blob) => format!("<blob, {} bytes>", blob.len()),
+    }
+}
+
+/// A path escaped for use in a `file:` URI
+fn uri_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            '%' | '?' | '#' => escaped.push_str(&format!("%{:02X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,305 @@
+use crate::{
+    lang::Language,
+    models::LineWindow,
+    outline::{matching_brace, skip_literal},
+};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Maximum length of a signature shown in the repository map
+const MAX_SIGNATURE_LEN: usize = 120;
+
+/// A top-level declaration such as a type, function or constant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopLevelSymbol {
+    pub name: String,
+    /// 1-based line of the declaration
+    pub line: usize,
+    /// First line of the declaration, without the opening brace
+    pub signature: String,
+}
+
+/// Top-level declarations of a Rust, TS/JS, Python or Go file, in source
+/// order. Only unindented declarations count, so methods and nested items
+/// are left out (Go methods are top-level and included).
+pub fn top_level_symbols(path: &str, content: &str) -> Vec<TopLevelSymbol> {
+    static RUST: OnceLock<Regex> = OnceLock::new();
+    static SCRIPT: OnceLock<Regex> = OnceLock::new();
+    static PYTHON: OnceLock<Regex> = OnceLock::new();
+    static GO: OnceLock<Regex> = OnceLock::new();
+
+    let Some(language) = Language::from_path(path) else {
+        return Vec::new();
+    };
+    let (cell, pattern) = match language {
+        Language::Rust => (
+            &RUST,
+            r#"^(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe|extern\s+"[^"]*")\s+)*(?:fn|struct|enum|trait|type|union|const|static|macro_rules!)\s+([A-Za-z_]\w*)"#,
+        ),
+        Language::JavaScript | Language::TypeScript => (
+            &SCRIPT,
+            r"^(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?:function\*?|class|interface|type|enum|const|let|var|namespace)\s+([A-Za-z_$][\w$]*)",
+        ),
+        Language::Python => (&PYTHON, r"^(?:async\s+)?(?:def|class)\s+([A-Za-z_]\w*)"),
+        Language::Go => (&GO, r"^(?:func\s*(?:\([^)]*\)\s*)?|type\s+)([A-Za-z_]\w*)"),
+        _ => return Vec::new(),
+    };
+    let re = cell.get_or_init(|| Regex::new(pattern).expect("valid symbol regex"));
+
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let name = re.captures(line)?.get(1)?.as_str().to_string();
+            let mut signature = line.trim_end().trim_end_matches('{').trim_end().to_string();
+            if signature.chars().count() > MAX_SIGNATURE_LEN {
+                signature = signature.chars().take(MAX_SIGNATURE_LEN).collect();
+                signature.push_str("...");
+            }
+            Some(TopLevelSymbol {
+                name,
+                line: index + 1,
+                signature,
+            })
+        })
+        .collect()
+}
+
+/// Find the lines of a named code item, including its doc comments,
+/// attributes and decorators. Nested items are addressed as `Type::method`
+/// (or `Type.method`): a method in a Rust `impl`/`trait`, a TS/JS or Python
+/// class, or a Go method with a `Type` receiver.
+pub fn find_symbol(path: &str, content: &str, name: &str) -> Option<LineWindow> {
+    let language = Language::from_path(path)?;
+    let segments: Vec<&str> = name
+        .split("::")
+        .flat_map(|segment| segment.split('.'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let (item, containers) = segments.split_last()?;
+    let lines: Vec<&str> = content.lines().collect();
+
+    let (start, end) = match language {
+        Language::Python => find_python(&lines, containers, item)?,
+        Language::Go => find_go(&lines, containers, item)?,
+        Language::Rust | Language::JavaScript | Language::TypeScript => {
+            find_braced(content, &lines, language, containers, item)?
+        }
+        _ => return None,
+    };
+    let start = extend_to_leading_comments(&lines, start, language);
+    Some(LineWindow {
+        start: start + 1,
+        end: end + 1,
+    })
+}
+
+/// Item declaration patterns for brace languages
+fn item_regex(language: Language, name: &str, nested: bool) -> Regex {
+    let name = regex::escape(name);
+    let pattern = match (language, nested) {
+        (Language::Rust, _) => format!(
+            r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe|default|extern\s+"[^"]*")\s+)*(?:fn|struct|enum|trait|type|union|mod|const|static|macro_rules!)\s+{}\b"#,
+            name
+        ),
+        (_, false) => format!(
+            r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?:function\*?|class|interface|type|enum|const|let|var|namespace)\s+{}\b",
+            name
+        ),
+        (_, true) => format!(
+            r"^\s*(?:(?:public|private|protected|static|async|readonly|override|abstract|get|set)\s+)*\*?{}\s*(?:<[^>]*>)?\s*(?:\(|[=:])",
+            name
+        ),
+    };
+    Regex::new(&pattern).expect("valid symbol regex")
+}
+
+/// Container declaration patterns (Rust impl/trait blocks, TS/JS classes)
+fn container_regex(language: Language, name: &str) -> Regex {
+    let name = regex::escape(name);
+    let pattern = match language {
+        Language::Rust => format!(
+            r"^\s*(?:(?:pub(?:\([^)]*\))?|unsafe)\s+)*(?:impl\b[^{{;]*?\b{name}\b|trait\s+{name}\b|mod\s+{name}\b)",
+            name = name
+        ),
+        _ => format!(
+            r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:class|interface|namespace)\s+{}\b",
+            name
+        ),
+    };
+    Regex::new(&pattern).expect("valid symbol regex")
+}
+
+/// Byte offset of the start of each line
+fn line_offsets(content: &str) -> Vec<usize> {
+    let mut offsets = vec![0];
+    offsets.extend(content.match_indices('\n').map(|(i, _)| i + 1));
+    offsets
+}
+
+/// Last line of the declaration starting at `start`: the line holding the
+/// brace that closes its body, or the line ending the declaration
+fn declaration_end(content: &str, lines: &[&str], start: usize, language: Language) -> usize {
+    let offsets = line_offsets(content);
+    let chars: Vec<char> = content[offsets[start]..].chars().collect();
+    let line_of = |char_index: usize| {
+        let byte = offsets[start]
+            + chars[..char_index]
+                .iter()
+                .map(|c| c.len_utf8())
+                .sum::<usize>();
+        offsets.partition_point(|&o| o <= byte) - 1
+    };
+
+    let mut depth = 0_i32;
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(end) = skip_literal(&chars, i, language) {
+            i = end;
+            continue;
+        }
+        match chars[i] {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            '{' if depth <= 0 => {
+                let end = matching_brace(&chars, i, language);
+                return line_of(end.saturating_sub(1)).min(lines.len() - 1);
+            }
+            ';' if depth <= 0 => return line_of(i),
+            // Go and JS/TS declarations may end at a newline without a semicolon
+            '\n' if depth <= 0 && language != Language::Rust => {
+                let line = lines[line_of(i)].trim_end();
+                let continues = ["=", ",", "(", "[", "=>", ":", "|", "&", "extends"]
+                    .iter()
+                    .any(|suffix| line.ends_with(suffix));
+                if !continues {
+                    return line_of(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    lines.len().saturating_sub(1)
+}
+
+/// Find an item in Rust or TS/JS, descending into containers
+fn find_braced(
+    content: &str,
+    lines: &[&str],
+    language: Language,
+    containers: &[&str],
+    item: &str,
+) -> Option<(usize, usize)> {
+    let mut ranges = vec![(0, lines.len())];
+    for container in containers {
+        let container_re = container_regex(language, container);
+        ranges = ranges
+            .into_iter()
+            .flat_map(|(from, to)| {
+                (from..to)
+                    .filter(|&i| container_re.is_match(lines[i]))
+                    .map(|i| (i + 1, declaration_end(content, lines, i, language)))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+    }
+
+    let item_re = item_regex(
+        language,
+        item,
+        !containers.is_empty() && language != Language::Rust,
+    );
+    ranges.into_iter().find_map(|(from, to)| {
+        let start = (from..to).find(|&i| item_re.is_match(lines[i]))?;
+        Some((start, declaration_end(content, lines, start, language)))
+    })
+}
+
+/// Find a Go function, type, or `Type.Method`
+fn find_go(lines: &[&str], containers: &[&str], item: &str) -> Option<(usize, usize)> {
+    let item_name = regex::escape(item);
+    let pattern = match containers {
+        [] => format!(
+            r"^(?:func\s+{n}\b|type\s+{n}\b|(?:var|const)\s+{n}\b)",
+            n = item_name
+        ),
+        [receiver] => format!(
+            r"^func\s*\(\s*\w*\s*\*?{}(?:\[[^\]]*\])?\s*\)\s*{}\b",
+            regex::escape(receiver),
+            item_name
+        ),
+        _ => return None,
+    };
+    let re = Regex::new(&pattern).expect("valid symbol regex");
+    let start = lines.iter().position(|line| re.is_match(line))?;
+    let content = lines.join("\n");
+    Some((start, declaration_end(&content, lines, start, Language::Go)))
+}
+
+/// Find a Python function or class, descending into classes
+fn find_python(lines: &[&str], containers: &[&str], item: &str) -> Option<(usize, usize)> {
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    // End of the indented block opened by the declaration at `start`
+    let block_end = |start: usize| {
+        let indent = indent_of(lines[start]);
+        let mut end = start;
+        let mut depth = 0_i32;
+        for (i, line) in lines.iter().enumerate().skip(start) {
+            let in_signature = depth > 0;
+            depth += line.matches(['(', '[', '{']).count() as i32;
+            depth -= line.matches([')', ']', '}']).count() as i32;
+            if i > start && !in_signature && !line.trim().is_empty() && indent_of(line) <= indent {
+                break;
+            }
+            if !line.trim().is_empty() {
+                end = i;
+            }
+        }
+        end
+    };
+    let declaration = |keyword: &str, name: &str| {
+        Regex::new(&format!(
+            r"^\s*(?:async\s+)?{}\s+{}\b",
+            keyword,
+            regex::escape(name)
+        ))
+        .expect("valid symbol regex")
+    };
+
+    let mut range = (0, lines.len());
+    for container in containers {
+        let re = declaration("class", container);
+        let start = (range.0..range.1).find(|&i| re.is_match(lines[i]))?;
+        range = (start + 1, block_end(start) + 1);
+    }
+    let re = declaration("(?:def|class)", item);
+    let start = (range.0..range.1).find(|&i| re.is_match(lines[i]))?;
+    Some((start, block_end(start)))
+}
+
+/// Move `start` up over doc comments, attributes and decorators directly above it
+pub(crate) fn extend_to_leading_comments(
+    lines: &[&str],
+    start: usize,
+    language: Language,
+) -> usize {
+    let is_leading = |line: &str| {
+        let line = line.trim_start();
+        match language {
+            Language::Python => line.starts_with('#') || line.starts_with('@'),
+            _ => {
+                line.starts_with("//")
+                    || line.starts_with("/*")
+                    || line.starts_with('*')
+                    || line.starts_with("#[")
+                    || line.starts_with('@')
+            }
+        }
+    };
+    let mut start = start;
+    while start > 0 && is_leading(lines[start - 1]) {
+        start -= 1;
+    }
+    start
+}
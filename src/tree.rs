@@ -3,7 +3,14 @@ use std::path::{Component, Path, PathBuf};
 
 /// Generate a directory tree from a list of file paths
 pub fn generate_tree(paths: &[PathBuf]) -> String {
-    if paths.is_empty() {
+    generate_tree_with_markers(paths, &HashMap::new())
+}
+
+/// Generate a directory tree, appending a marker (e.g. a git status code like
+/// `M` or `??`) to each file whose slash-separated path appears in `markers`.
+/// Marked paths missing from `paths` (such as deleted files) are added to the tree.
+pub fn generate_tree_with_markers(paths: &[PathBuf], markers: &HashMap<String, String>) -> String {
+    if paths.is_empty() && markers.is_empty() {
         return String::new();
     }
 
@@ -19,6 +26,15 @@ pub fn generate_tree(paths: &[PathBuf]) -> String {
         add_path_to_tree(&mut tree, path);
     }
 
+    // Attach markers, adding marked paths that are not part of the listing
+    for (path, marker) in markers {
+        let path = Path::new(path);
+        add_path_to_tree(&mut tree, path);
+        if let Some(node) = find_node_mut(&mut tree, path) {
+            node.marker = Some(marker.clone());
+        }
+    }
+
     // Generate the tree output
     output.push_str("Directory structure:\n");
     render_tree(&tree, &mut output, "", true);
@@ -32,6 +48,7 @@ struct TreeNode {
     name: String,
     children: HashMap<String, TreeNode>,
     is_file: bool,
+    marker: Option<String>,
 }
 
 impl TreeNode {
@@ -40,6 +57,7 @@ impl TreeNode {
             name: String::new(),
             children: HashMap::new(),
             is_file: false,
+            marker: None,
         }
     }
 
@@ -48,10 +66,20 @@ impl TreeNode {
             name,
             children: HashMap::new(),
             is_file,
+            marker: None,
         }
     }
 }
 
+/// Find the node for `path` in the tree, if present
+fn find_node_mut<'a>(root: &'a mut TreeNode, path: &Path) -> Option<&'a mut TreeNode> {
+    let mut current = root;
+    for name in clean_path_components(path) {
+        current = current.children.get_mut(&name)?;
+    }
+    Some(current)
+}
+
 /// Filter out Windows drive prefixes and root directory components to get logical path components.
 /// This ensures that paths like "C:\repo\src\lib.rs" become ["repo", "src", "lib.rs"]
 /// instead of ["C:", "\", "repo", "src", "lib.rs"].
@@ -172,6 +200,11 @@ fn render_child(
     if !child.is_file {
         output.push('/');
     }
+    if let Some(marker) = &child.marker {
+        output.push_str(" [");
+        output.push_str(marker);
+        output.push(']');
+    }
     output.push('\n');
 
     // Calculate next prefix for children
@@ -0,0 +1,98 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{
+    binary::{describe_binary, detect_mime, image_dimensions, parse_lfs_pointer, LfsPointer},
+    config::YekConfig,
+    serialize_repo,
+};
+
+/// The start of a PNG: signature and IHDR chunk
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    png.extend_from_slice(&width.to_be_bytes());
+    png.extend_from_slice(&height.to_be_bytes());
+    png.extend_from_slice(&[8, 6, 0, 0, 0, 0, 0, 0, 0]);
+    png
+}
+
+const LFS_POINTER: &str = "version https://git-lfs.github.com/spec/v1\n\
+    oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+    size 12345\n";
+
+#[test]
+fn test_image_dimensions_and_mime() {
+    assert_eq!(image_dimensions(&png(640, 480)), Some((640, 480)));
+    assert_eq!(
+        image_dimensions(b"GIF89a\x20\x03\x58\x02\0\0"),
+        Some((800, 600))
+    );
+
+    // A JPEG with an APP0 segment before the baseline start of frame
+    let jpeg = b"\xff\xd8\xff\xe0\0\x04JF\xff\xc0\0\x11\x08\x01\xe0\x02\x80\x03";
+    assert_eq!(image_dimensions(jpeg), Some((640, 480)));
+    assert_eq!(image_dimensions(b"\x7fELF\x02\x01\x01"), None);
+
+    assert_eq!(detect_mime("logo.bin", &png(1, 1)), "image/png");
+    assert_eq!(
+        detect_mime("app.jar", b"PK\x03\x04\x14\0"),
+        "application/java-archive"
+    );
+    assert_eq!(
+        detect_mime("data.xyz", b"\0\x02\x03"),
+        "application/octet-stream"
+    );
+
+    assert_eq!(
+        describe_binary("logo.png", &png(640, 480)),
+        "[Binary file: image/png, 33 B, 640x480, \
+         sha256 e0e6fd836f2c429c829e082ee65f96870ef1ac2b9a72f83860398ad2b86c62e6]\n"
+    );
+}
+
+#[test]
+fn test_parse_lfs_pointer() {
+    assert_eq!(
+        parse_lfs_pointer(LFS_POINTER.as_bytes()),
+        Some(LfsPointer {
+            oid: "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393".to_string(),
+            size: 12345,
+        })
+    );
+    assert_eq!(parse_lfs_pointer(b"version 1\nsize 3\n"), None);
+}
+
+#[test]
+fn test_binary_placeholders_option() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("logo.png"), png(64, 32)).unwrap();
+    fs::write(dir.path().join("video.mp4"), LFS_POINTER).unwrap();
+    fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+
+    let mut config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert!(!output.contains("logo.png"));
+    assert_eq!(files.len(), 2);
+    // LFS pointers are labelled even without placeholders
+    assert!(output.contains(
+        ">>>> video.mp4\n[Git LFS pointer: video/mp4, 12.1 KiB, \
+         sha256 4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393]\n"
+    ));
+    assert!(!output.contains("version https://git-lfs"));
+
+    config.binary_placeholders = true;
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> logo.png\n[Binary file: image/png, 33 B, 64x32, sha256 "));
+    assert!(output.contains(
+        ">>>> video.mp4\n[Git LFS pointer: video/mp4, 12.1 KiB, \
+         sha256 4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393]\n"
+    ));
+    assert_eq!(files.len(), 3);
+
+    // Files with a binary extension passed directly get a placeholder too
+    config.input_paths = vec![dir.path().join("logo.png").to_string_lossy().to_string()];
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains("[Binary file: image/png"));
+}
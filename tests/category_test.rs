@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod category_tests {
-    use yek::category::{categorize_file, CategoryWeights, FileCategory};
+    use std::fs;
+    use tempfile::tempdir;
+    use yek::category::{categorize_file, is_generated_file, CategoryWeights, FileCategory};
+    use yek::config::YekConfig;
     use yek::priority::{get_file_priority_with_category, PriorityRule};
 
     #[test]
@@ -127,6 +130,7 @@ mod category_tests {
             test: 50,
             configuration: 25,
             documentation: 10,
+            generated: 0,
             other: 5,
         };
         assert_eq!(custom_weights.get_offset(FileCategory::Source), 100);
@@ -237,9 +241,95 @@ mod category_tests {
         assert_eq!(FileCategory::Test.name(), "test");
         assert_eq!(FileCategory::Configuration.name(), "configuration");
         assert_eq!(FileCategory::Documentation.name(), "documentation");
+        assert_eq!(FileCategory::Generated.name(), "generated");
         assert_eq!(FileCategory::Other.name(), "other");
     }
 
+    #[test]
+    fn test_is_generated_file() {
+        // Generator file names
+        assert!(is_generated_file("dist/app.min.js", b"var a=1;\n"));
+        assert!(is_generated_file("api/service.pb.go", b"package api\n"));
+        assert!(is_generated_file("proto/user_pb2.py", b"import x\n"));
+        assert!(is_generated_file("lib/model.g.dart", b"part of x;\n"));
+        assert!(is_generated_file("src/schema.generated.ts", b"export {}\n"));
+
+        // Markers in a comment near the top
+        assert!(is_generated_file(
+            "src/parser.rs",
+            b"// @generated by build.rs\nfn parse() {}\n"
+        ));
+        assert!(is_generated_file(
+            "api/client.go",
+            b"// Code generated by mockgen. DO NOT EDIT.\n\npackage api\n"
+        ));
+        assert!(is_generated_file(
+            "Resources.cs",
+            b"// <auto-generated>\n//     This code was generated by a tool.\n"
+        ));
+
+        // Generic phrases, markers outside comments or far from the top don't count
+        assert!(!is_generated_file(
+            "models.py",
+            b"# This file was automatically generated\nclass A: pass\n"
+        ));
+        assert!(!is_generated_file(
+            "settings.py",
+            b"# DO NOT EDIT without asking the platform team\nDEBUG = False\n"
+        ));
+        assert!(!is_generated_file(
+            "src/lint.rs",
+            b"const MARKER: &str = \"@generated\";\n"
+        ));
+        let late_marker = format!("{}// DO NOT EDIT\n", "fn f() {}\n".repeat(20));
+        assert!(!is_generated_file("src/late.rs", late_marker.as_bytes()));
+
+        // Minified JS and CSS: a very long line with hardly any whitespace
+        let minified = "function f(a,b){return a+b}".repeat(200);
+        assert!(is_generated_file("dist/app.js", minified.as_bytes()));
+        assert!(!is_generated_file("src/main.rs", b"fn main() {}\n"));
+        // Long lines of prose or compact data are not minified code
+        let paragraph = "This paragraph is written on a single line. ".repeat(100);
+        assert!(!is_generated_file("README.md", paragraph.as_bytes()));
+        let data = format!("[{}]", "{\"id\":1,\"name\":\"item\"},".repeat(200));
+        assert!(!is_generated_file("data.json", data.as_bytes()));
+        assert!(!is_generated_file("dist/data.js", paragraph.as_bytes()));
+    }
+
+    #[test]
+    fn test_generated_files_skipped_by_default() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("app.min.js"), "var a=1;\n").unwrap();
+        let readme = format!("# Guide\n\n{}\n", "An unwrapped paragraph. ".repeat(200));
+        fs::write(dir.path().join("README.md"), &readme).unwrap();
+        fs::write(
+            dir.path().join("schema.rs"),
+            "// @generated\npub struct Schema;\n",
+        )
+        .unwrap();
+
+        let mut config = YekConfig {
+            input_paths: vec![dir.path().to_string_lossy().to_string()],
+            ..Default::default()
+        };
+        let (output, files) = yek::serialize_repo(&config).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(output.contains("fn main()"));
+        assert!(!output.contains("app.min.js"));
+        // A long-paragraph README is kept
+        assert!(output.contains(">>>> README.md\n# Guide"));
+
+        config.include_generated = true;
+        let (_, files) = yek::serialize_repo(&config).unwrap();
+        assert_eq!(files.len(), 4);
+        let schema = files.iter().find(|f| f.rel_path == "schema.rs").unwrap();
+        assert_eq!(schema.category, FileCategory::Generated);
+        // Generated files come first, before the source file
+        let main = files.iter().find(|f| f.rel_path == "main.rs").unwrap();
+        assert!(schema.priority < main.priority);
+    }
+
     #[test]
     fn test_priority_with_custom_weights() {
         let rules = vec![PriorityRule {
@@ -252,6 +342,7 @@ mod category_tests {
             test: 100,
             configuration: 25,
             documentation: 10,
+            generated: 0,
             other: 5,
         };
 
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{config_for, file, project};
+use yek::{chunks::chunk_file, config::YekConfig, serialize_repo};
+
+#[test]
+fn test_chunks_follow_item_boundaries() {
+    let body = "    let x = 1;\n".repeat(10);
+    let content = format!(
+        "use std::fmt;\n\n/// First\npub fn first() {{\n{body}}}\n\n/// Second\npub fn second() {{\n{body}}}\n"
+    );
+    let chunks = chunk_file(&file("lib.rs", &content), 120, 2);
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 16));
+    assert_eq!((chunks[1].start_line, chunks[1].end_line), (17, 29));
+    assert!(chunks[1].content.starts_with("/// Second\npub fn second()"));
+    assert_eq!(chunks[0].language, Some("rust"));
+    assert_eq!(chunks[0].category, "source");
+    assert_eq!(chunks[0].hash.len(), 64);
+    assert!(chunks[0].tokens > 0 && chunks[0].tokens <= 120);
+
+    // Small sections are merged into one chunk
+    let chunks = chunk_file(&file("lib.rs", &content), 1000, 2);
+    assert_eq!(chunks.len(), 1);
+    assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 29));
+}
+
+#[test]
+fn test_chunks_follow_markdown_headings() {
+    let content =
+        "# Title\n\nIntro.\n\n## Usage\n\n```sh\n# not a heading\n```\n\n## License\n\nMIT\n";
+    let chunks = chunk_file(&file("README.md", content), 20, 0);
+    let starts: Vec<usize> = chunks.iter().map(|c| c.start_line).collect();
+    assert_eq!(starts, vec![1, 5, 11]);
+    assert_eq!(chunks[0].language, None);
+    assert_eq!(chunks[0].category, "documentation");
+}
+
+#[test]
+fn test_chunks_fall_back_to_overlapping_windows() {
+    let content: String = (1..=40).map(|i| format!("value {}\n", i)).collect();
+    let chunks = chunk_file(&file("data.txt", &content), 30, 2);
+
+    assert!(chunks.len() > 1);
+    assert_eq!(chunks[0].start_line, 1);
+    assert_eq!(chunks.last().unwrap().end_line, 40);
+    for pair in chunks.windows(2) {
+        assert_eq!(pair[1].start_line, pair[0].end_line - 1);
+        assert!(pair[0].tokens <= 30);
+    }
+}
+
+#[test]
+fn test_format_chunks_jsonl() {
+    let dir = project(&[("a.rs", "pub fn a() {}\n"), ("b.md", "# B\n\nText\n")]);
+
+    let mut config = YekConfig {
+        format: Some("chunks-jsonl".to_string()),
+        ..config_for(dir.path())
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    let records: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+    let a = records.iter().find(|r| r["path"] == "a.rs").unwrap();
+    assert_eq!(a["start_line"], 1);
+    assert_eq!(a["end_line"], 1);
+    assert_eq!(a["language"], "rust");
+    assert_eq!(a["content"], "pub fn a() {}\n");
+    assert!(!output.contains(">>>>"));
+
+    config.format = Some("parquet".to_string());
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("format"));
+    config.format = Some("chunks-jsonl".to_string());
+    config.json = true;
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,80 @@
+//! Helpers shared by the integration tests. Each test crate uses only some
+//! of them.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::{tempdir, TempDir};
+use yek::{config::YekConfig, models::ProcessedFile};
+
+/// A processed file with priority 0
+pub fn file(rel_path: &str, content: &str) -> ProcessedFile {
+    file_with_priority(rel_path, content, 0)
+}
+
+pub fn file_with_priority(rel_path: &str, content: &str, priority: i32) -> ProcessedFile {
+    ProcessedFile::new(rel_path.to_string(), content.to_string(), priority, 0)
+}
+
+/// A temporary directory holding `files`, given as (relative path, content)
+pub fn project(files: &[(&str, &str)]) -> TempDir {
+    let dir = tempdir().unwrap();
+    for (rel_path, content) in files {
+        let path = dir.path().join(rel_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+/// The default configuration, serializing `path`
+pub fn config_for(path: &Path) -> YekConfig {
+    YekConfig {
+        input_paths: vec![path.to_string_lossy().to_string()],
+        ..Default::default()
+    }
+}
+
+/// Run git in `repo_path`, failing the test if it fails
+pub fn git(repo_path: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Create a repository on branch `main` with a test identity
+pub fn init_repo(repo_path: &Path) {
+    git(repo_path, &["init", "-b", "main"]);
+    git(repo_path, &["config", "user.name", "Test User"]);
+    git(repo_path, &["config", "user.email", "test@example.com"]);
+}
+
+pub fn commit_file(repo_path: &Path, name: &str, content: &str, message: &str) {
+    fs::write(repo_path.join(name), content).unwrap();
+    git(repo_path, &["add", name]);
+    git(repo_path, &["commit", "-m", message]);
+}
+
+/// Commit a file with the given author, authored and committed at `date`
+pub fn commit_file_as(repo_path: &Path, name: &str, content: &str, author: &str, date: &str) {
+    fs::write(repo_path.join(name), content).unwrap();
+    git(repo_path, &["add", name]);
+    let message = format!("Add {}", name);
+    let output = Command::new("git")
+        .args(["commit", "-m", &message, "--author", author])
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_DATE", date)
+        .current_dir(repo_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
@@ -81,6 +81,43 @@ fn test_validate_config_invalid_ignore_pattern() {
     assert!(err.contains("Invalid pattern"));
 }
 
+#[test]
+fn test_validate_config_commits_matching() {
+    let mut config = YekConfig::extend_config_with_defaults(vec![], "/tmp/yek".to_string());
+    config.commits_matching = Some("(".to_string());
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("commits_matching"));
+
+    config.commits_matching = Some("ABC-\\d+".to_string());
+    config.since = Some("last tuesday".to_string());
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("since"));
+
+    config.commits_matching = None;
+    config.since = Some("2w".to_string());
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("commits_matching"));
+}
+
+#[test]
+fn test_validate_config_line_range() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("lib.rs");
+    fs::write(&file, "fn main() {}\n").unwrap();
+    let file = file.to_string_lossy().to_string();
+
+    let mut config = YekConfig::extend_config_with_defaults(vec![], "/tmp/yek".to_string());
+    config.input_paths = vec![format!("{}:40-10", file)];
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("input_paths: Invalid line range"));
+
+    config.input_paths = vec![format!("{}:0+5", file)];
+    assert!(config.validate().is_err());
+
+    config.input_paths = vec![format!("{}:10-40", file), format!("{}:1+1", file)];
+    assert!(config.validate().is_ok());
+}
+
 #[test]
 fn test_validate_config_tree_header_mutual_exclusivity() {
     let mut config = YekConfig::extend_config_with_defaults(vec![], "/tmp/yek".to_string());
@@ -0,0 +1,103 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{config::YekConfig, data::summarize_data, serialize_repo};
+
+fn users(count: usize) -> String {
+    let records: Vec<String> = (0..count)
+        .map(|i| {
+            let email = if i % 2 == 1 {
+                format!(",\"email\":\"u{i}@example.org\"")
+            } else {
+                String::new()
+            };
+            format!("{{\"id\":{i},\"name\":\"user{i}\",\"address\":{{\"city\":\"Paris\"}}{email}}}")
+        })
+        .collect();
+    format!("[{}]", records.join(","))
+}
+
+#[test]
+fn test_summarize_json_records() {
+    let summary = summarize_data("data/users.json", &users(50), 2).unwrap();
+    assert_eq!(
+        summary,
+        "JSON data summary: 50 records, showing the schema and the first 2\n\
+         schema:\n\
+         \x20 address: object\n\
+         \x20   city: string (e.g. \"Paris\")\n\
+         \x20 id: integer (e.g. 0)\n\
+         \x20 name: string (e.g. \"user0\")\n\
+         \x20 email?: string (e.g. \"u1@example.org\")\n\
+         first records:\n\
+         {\"address\":{\"city\":\"Paris\"},\"id\":0,\"name\":\"user0\"}\n\
+         {\"address\":{\"city\":\"Paris\"},\"email\":\"u1@example.org\",\"id\":1,\"name\":\"user1\"}\n"
+    );
+
+    // Records wrapped in an object are found in its largest array
+    let wrapped = format!("{{\"version\":2,\"items\":{}}}", users(10));
+    let summary = summarize_data("wrapped.json", &wrapped, 1).unwrap();
+    assert!(summary.starts_with("JSON data summary: 10 records in \"items\""));
+    assert!(summary.ends_with("other fields:\n  version: integer (e.g. 2)\n"));
+
+    assert!(summarize_data("broken.json", "[1, 2", 3).is_none());
+    assert!(summarize_data("main.rs", "[1, 2]", 3).is_none());
+}
+
+#[test]
+fn test_summarize_tables_yaml_and_ndjson() {
+    let csv: String = std::iter::once("id,name,score,active\n".to_string())
+        .chain((0..20).map(|i| format!("{i},\"Name, {i}\",{}.5,\n", i)))
+        .collect();
+    let summary = summarize_data("rows.csv", &csv, 2).unwrap();
+    assert_eq!(
+        summary,
+        "CSV data summary: 20 rows, 4 columns, showing the schema and the first 2\n\
+         schema:\n\
+         \x20 id: integer (e.g. 0)\n\
+         \x20 name: string (e.g. \"Name, 0\")\n\
+         \x20 score: number (e.g. 0.5)\n\
+         \x20 active: null\n\
+         first rows:\n\
+         id,name,score,active\n\
+         0,\"Name, 0\",0.5,\n\
+         1,\"Name, 1\",1.5,\n"
+    );
+
+    let yaml = "- name: web\n  port: 80\n- name: db\n  port: 5432\n  replicas: 2\n";
+    let summary = summarize_data("services.yaml", yaml, 5).unwrap();
+    assert!(summary.starts_with("YAML data summary: 2 records, showing the schema and the first 2"));
+    assert!(summary.contains("  replicas?: integer (e.g. 2)\n"));
+
+    let ndjson = "{\"ts\":1,\"kind\":\"click\"}\n\n{\"ts\":2,\"kind\":null}\n";
+    let summary = summarize_data("events.ndjson", ndjson, 1).unwrap();
+    assert!(summary.contains("  kind: null | string (e.g. \"click\")\n"));
+    assert!(summary.ends_with("first records:\n{\"kind\":\"click\",\"ts\":1}\n"));
+}
+
+#[test]
+fn test_summarize_data_option() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("users.json"), users(100)).unwrap();
+    fs::write(dir.path().join("small.json"), "{\"a\": 1}\n").unwrap();
+
+    let config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        summarize_data: Some("1KB".to_string()),
+        data_sample: 1,
+        ..Default::default()
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> users.json\nJSON data summary: 100 records"));
+    assert!(!output.contains("user99"));
+    // Files below the threshold are kept as they are
+    assert!(output.contains(">>>> small.json\n{\"a\": 1}"));
+
+    let invalid = YekConfig {
+        summarize_data: Some("lots".to_string()),
+        ..config
+    };
+    let error = invalid.validate().unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("summarize_data: Invalid size format"));
+}
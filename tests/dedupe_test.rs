@@ -0,0 +1,178 @@
+mod common;
+
+use common::{config_for, file_with_priority as file, project};
+use yek::{
+    config::YekConfig,
+    dedupe::{dedupe_files, simhash},
+    serialize_repo,
+};
+
+fn locale(extra: &str) -> String {
+    let mut content: String = (0..60)
+        .map(|i| format!("\"message_{i}\": \"Translated text number {i} for the app\",\n"))
+        .collect();
+    content.push_str(extra);
+    content
+}
+
+#[test]
+fn test_dedupe_identical_files() {
+    let mut files = vec![
+        file("vendor/config.json", "{\"a\": 1}\n", 1),
+        file("config.json", "{\"a\": 1}\n", 5),
+        file("other.json", "{\"b\": 2}\n", 0),
+        file("empty_a.py", "", 0),
+        file("empty_b.py", "", 0),
+    ];
+    assert_eq!(dedupe_files(&mut files, None), 1);
+
+    // The higher-priority copy is the representative
+    assert!(files[1].duplicate.is_none());
+    let duplicate = files[0].duplicate.as_ref().unwrap();
+    assert_eq!(duplicate.describe(), "identical to config.json");
+    assert_eq!(files[0].content, "");
+    assert_eq!(files[0].size_bytes, 0);
+    assert!(files[2].duplicate.is_none());
+    assert!(files[3].duplicate.is_none() && files[4].duplicate.is_none());
+}
+
+#[test]
+fn test_dedupe_similar_files() {
+    let mut files = vec![
+        file("en.json", &locale(""), 0),
+        file("en_GB.json", &locale("\"extra\": \"colour\",\n"), 0),
+        file(
+            "main.rs",
+            "fn main() {\n    println!(\"unrelated\");\n}\n",
+            0,
+        ),
+    ];
+    // Exact matching alone keeps near-duplicates
+    assert_eq!(dedupe_files(&mut files.clone(), None), 0);
+
+    assert_eq!(dedupe_files(&mut files, Some(90)), 1);
+    let duplicate = files[1].duplicate.as_ref().unwrap();
+    assert_eq!(duplicate.of, "en.json");
+    assert!(duplicate.similarity >= 90 && duplicate.similarity < 100);
+    assert!(duplicate.describe().ends_with("% similar to en.json"));
+    assert!(files[2].duplicate.is_none());
+}
+
+#[test]
+fn test_dedupe_similarity_matches_pairwise_comparison() {
+    // Files drawn from a small vocabulary, some of them edits of others
+    let mut seed = 7_u64;
+    let mut next = move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize
+    };
+    let words = [
+        "alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta", "theta",
+    ];
+    let mut contents: Vec<Vec<&str>> = Vec::new();
+    for i in 0..150 {
+        let content = if i % 3 == 2 {
+            let mut edited = contents[next() % contents.len()].clone();
+            let at = next() % edited.len();
+            edited[at] = words[next() % words.len()];
+            edited
+        } else {
+            (0..40).map(|_| words[next() % words.len()]).collect()
+        };
+        contents.push(content);
+    }
+    let files: Vec<_> = contents
+        .iter()
+        .enumerate()
+        .map(|(i, words)| file(&format!("f{i:03}.txt"), &words.join(" "), 0))
+        .collect();
+
+    for threshold in [60, 75, 90, 100] {
+        // Compare every file with every representative chosen before it
+        let fingerprints: Vec<u64> = files.iter().map(|f| simhash(&f.content)).collect();
+        let mut representatives: Vec<usize> = Vec::new();
+        let mut expected: Vec<Option<String>> = Vec::new();
+        for (index, file) in files.iter().enumerate() {
+            let identical = representatives
+                .iter()
+                .find(|&&original| files[original].content == file.content);
+            let original = identical.or_else(|| {
+                representatives.iter().find(|&&original| {
+                    let (a, b) = (file.content.len(), files[original].content.len());
+                    let matching = 64 - (fingerprints[index] ^ fingerprints[original]).count_ones();
+                    matching * 100 / 64 >= threshold && a.min(b) * 2 >= a.max(b)
+                })
+            });
+            match original {
+                Some(&original) => expected.push(Some(files[original].rel_path.clone())),
+                None => {
+                    representatives.push(index);
+                    expected.push(None);
+                }
+            }
+        }
+
+        assert!(expected.iter().any(Option::is_some));
+
+        let mut deduped = files.clone();
+        dedupe_files(&mut deduped, Some(threshold as u8));
+        let actual: Vec<Option<String>> = deduped
+            .iter()
+            .map(|f| f.duplicate.as_ref().map(|d| d.of.clone()))
+            .collect();
+        assert_eq!(actual, expected, "threshold {}", threshold);
+    }
+}
+
+#[test]
+fn test_dedupe_output() {
+    let dir = project(&[
+        ("a.toml", "name = \"x\"\n"),
+        ("copy/a.toml", "name = \"x\"\n"),
+    ]);
+
+    let mut config = YekConfig {
+        dedupe: true,
+        ..config_for(dir.path())
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert_eq!(output.matches("name = \"x\"").count(), 1);
+    assert!(output.contains("identical to "));
+
+    config.json = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let duplicate = value
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry.get("duplicate_of").is_some())
+        .unwrap();
+    assert_eq!(duplicate["similarity"], 100);
+
+    config.dedupe_similarity = Some(0);
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_dedupe_drops_references_to_files_over_budget() {
+    let large = "x".repeat(2000);
+    let dir = project(&[
+        ("a.txt", &large),
+        ("copy/a.txt", &large),
+        ("small.txt", "small\n"),
+    ]);
+
+    let config = YekConfig {
+        dedupe: true,
+        max_size: "1KB".to_string(),
+        ..config_for(dir.path())
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    // The representative doesn't fit, so its duplicate can't refer to it
+    assert!(output.contains(">>>> small.txt"));
+    assert!(!output.contains(">>>> a.txt"));
+    assert!(!output.contains("identical to a.txt"));
+}
@@ -0,0 +1,166 @@
+use flate2::{write::ZlibEncoder, Compression};
+use std::fs;
+use std::io::Write;
+use tempfile::tempdir;
+use yek::{config::YekConfig, documents::extract_text, serialize_repo};
+
+fn zip_file(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in entries {
+        writer
+            .start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+/// A two-page PDF: a simple font with an uncompressed content stream, and
+/// a composite font mapped through a compressed ToUnicode CMap
+fn pdf() -> Vec<u8> {
+    let compress = |data: &[u8]| {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    };
+    let page_one =
+        b"BT /F1 12 Tf 72 720 Td (Design spec) Tj 0 -14 Td (Caf\\351 \\(v2\\)) Tj ET".to_vec();
+    let cmap = b"/CIDInit /ProcSet findresource begin\n1 begincodespacerange <0000> <FFFF> endcodespacerange\n\
+                 2 beginbfchar <0001> <0048> <0002> <0069> endbfchar\n\
+                 1 beginbfrange <0003> <0004> <0041> endbfrange\nendcmap"
+        .to_vec();
+    let page_two = b"BT /F2 10 Tf 1 0 0 1 72 700 Tm [<00010002> -500 <00030004>] TJ ET".to_vec();
+
+    let stream = |dict: &str, data: &[u8]| {
+        let mut object = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+        object.extend_from_slice(data);
+        object.extend_from_slice(b"\nendstream");
+        object
+    };
+    let objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 /Resources << /Font << /F1 5 0 R /F2 6 0 R >> >> >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Contents 7 0 R >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Contents [8 0 R] >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type0 /BaseFont /Noto /ToUnicode 9 0 R >>".to_vec(),
+        stream("", &page_one),
+        stream("/Filter /FlateDecode", &compress(&page_two)),
+        stream("/Filter [/FlateDecode]", &compress(&cmap)),
+    ];
+    pdf_file(&objects)
+}
+
+/// A PDF holding `objects`, numbered from 1
+fn pdf_file(objects: &[Vec<u8>]) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.7\n".to_vec();
+    for (index, object) in objects.iter().enumerate() {
+        pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    pdf.extend_from_slice(b"trailer\n<< /Root 1 0 R >>\n%%EOF\n");
+    pdf
+}
+
+#[test]
+fn test_extract_pdf_text() {
+    assert_eq!(
+        extract_text("specs/design.pdf", &pdf(), 1024).unwrap(),
+        "[Text extracted from PDF document, 2 pages]\n\n\
+         Design spec\nCafé (v2)\n\nHi AB\n"
+    );
+    assert!(extract_text("broken.pdf", b"%PDF-1.4\nnot really", 1024).is_none());
+}
+
+#[test]
+fn test_malformed_pdfs_are_skipped() {
+    // Cut off anywhere, a file must not crash extraction
+    let full = pdf();
+    for end in 0..full.len() {
+        extract_text("cut.pdf", &full[..end], 1024);
+    }
+    let first_stream = full.windows(6).position(|w| w == b"stream").unwrap();
+    assert!(extract_text("cut.pdf", &full[..first_stream], 1024).is_none());
+
+    // A page whose content is `content`
+    let single_page = |content: Vec<u8>| {
+        pdf_file(&[
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            b"<< /Type /Page /Parent 2 0 R /Contents 4 0 R >>".to_vec(),
+            content,
+        ])
+    };
+    let overflowing_length = single_page(
+        b"<< /Length 18446744073709551615 >>\nstream\nBT (Hidden) Tj ET\nendstream".to_vec(),
+    );
+    assert!(extract_text("length.pdf", &overflowing_length, 1024).is_none());
+
+    let overflowing_offset = single_page(
+        b"<< /Type /ObjStm /N 1 /First 18446744073709551615 /Length 4 >>\nstream\n5 10\nendstream"
+            .to_vec(),
+    );
+    assert!(extract_text("objstm.pdf", &overflowing_offset, 1024).is_none());
+
+    let deeply_nested = single_page("[".repeat(200_000).into_bytes());
+    assert!(extract_text("nested.pdf", &deeply_nested, 1024).is_none());
+    let mut nested_content = b"<< /Length 200000 >>\nstream\n".to_vec();
+    nested_content.extend_from_slice("[".repeat(200_000).as_bytes());
+    nested_content.extend_from_slice(b"\nendstream");
+    assert!(extract_text("nested.pdf", &single_page(nested_content), 1024).is_none());
+}
+
+#[test]
+fn test_extract_docx_and_odt_text() {
+    let document = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>
+<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Overview</w:t></w:r></w:p>
+<w:p><w:r><w:t xml:space="preserve">Cache &amp; queue </w:t></w:r><w:r><w:t>design</w:t></w:r></w:p>
+<w:p><w:r><w:t>Limits:</w:t><w:tab/><w:t>10 &lt; n</w:t></w:r></w:p>
+</w:body></w:document>"#;
+    let docx = zip_file(&[("word/document.xml", document)]);
+    assert_eq!(
+        extract_text("spec.docx", &docx, 1024).unwrap(),
+        "[Text extracted from DOCX document]\n\n# Overview\nCache & queue design\nLimits:\t10 < n\n"
+    );
+
+    let content = r#"<office:document-content><office:body><office:text>
+<text:h text:outline-level="2">Goals</text:h>
+<text:p>Fast<text:s text:c="2"/>and<text:line-break/>simple</text:p>
+</office:text></office:body></office:document-content>"#;
+    let odt = zip_file(&[("content.xml", content)]);
+    assert_eq!(
+        extract_text("notes.odt", &odt, 1024).unwrap(),
+        "[Text extracted from ODT document]\n\n## Goals\nFast  and\nsimple\n"
+    );
+
+    // Text beyond the size cap is cut off with a note
+    let truncated = extract_text("spec.docx", &docx, 12).unwrap();
+    assert!(truncated.ends_with("# Overview\nC\n\n[Truncated: 34 more bytes of extracted text]\n"));
+}
+
+#[test]
+fn test_extract_documents_option() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("design.pdf"), pdf()).unwrap();
+    fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+
+    let mut config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        ..Default::default()
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(!output.contains("design.pdf"));
+
+    config.extract_documents = true;
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> design.pdf\n[Text extracted from PDF document, 2 pages]"));
+    assert_eq!(files.len(), 2);
+
+    // Passing the document directly works too, despite its binary extension
+    config.input_paths = vec![dir.path().join("design.pdf").to_string_lossy().to_string()];
+    config.binary_extensions = vec!["pdf".to_string()];
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains("Design spec"));
+}
@@ -0,0 +1,486 @@
+mod common;
+
+use common::{commit_file, commit_file_as, config_for, file, git, init_repo};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+use yek::{
+    blame::{attach_blame, format_age, format_content_with_blame, BlameCache},
+    config::YekConfig,
+    git::collect_git_metadata,
+    priority::parse_since,
+    serialize_repo,
+};
+
+#[test]
+fn test_collect_git_metadata_not_a_repo() {
+    let dir = tempdir().unwrap();
+    assert!(collect_git_metadata(dir.path(), 5).is_none());
+}
+
+#[test]
+fn test_collect_git_metadata_clean_repo() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "a", "First commit");
+    commit_file(dir.path(), "b.txt", "b", "Second commit");
+    commit_file(dir.path(), "c.txt", "c", "Third commit");
+
+    let metadata = collect_git_metadata(dir.path(), 2).unwrap();
+    assert_eq!(metadata.branch.as_deref(), Some("main"));
+    assert_eq!(metadata.head.len(), 40);
+    assert_eq!(metadata.head_subject, "Third commit");
+    assert!(!metadata.dirty);
+    assert!(metadata.status.is_empty());
+    assert_eq!(metadata.recent_commits.len(), 2);
+    assert_eq!(metadata.recent_commits[0].subject, "Third commit");
+    assert_eq!(metadata.recent_commits[1].subject, "Second commit");
+}
+
+#[test]
+fn test_collect_git_metadata_dirty_repo() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "a", "First commit");
+    fs::write(dir.path().join("a.txt"), "changed").unwrap();
+    fs::write(dir.path().join("new.txt"), "new").unwrap();
+
+    let metadata = collect_git_metadata(dir.path(), 5).unwrap();
+    assert!(metadata.dirty);
+    let codes: Vec<(&str, &str)> = metadata
+        .status
+        .iter()
+        .map(|e| (e.code.as_str(), e.path.as_str()))
+        .collect();
+    assert_eq!(codes, vec![("M", "a.txt"), ("??", "new.txt")]);
+}
+
+#[test]
+fn test_git_header_in_text_output() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "hello", "Add greeting");
+
+    let mut config = config_for(dir.path());
+    config.git_header = true;
+    config.tree_header = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+
+    assert!(output.starts_with("Git repository:\n"));
+    assert!(output.contains("  Branch: main\n"));
+    assert!(output.contains("Add greeting"));
+    assert!(output.contains("  Worktree: clean\n"));
+    // Git header comes before the tree header
+    let git_pos = output.find("Git repository:").unwrap();
+    let tree_pos = output.find("Directory structure:").unwrap();
+    assert!(git_pos < tree_pos);
+    assert!(output.contains(">>>> a.txt\nhello"));
+}
+
+#[test]
+fn test_git_header_in_json_output() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "hello", "Add greeting");
+
+    let mut config = config_for(dir.path());
+    config.git_header = true;
+    config.json = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value["git"]["branch"], "main");
+    assert_eq!(value["git"]["head_subject"], "Add greeting");
+    assert_eq!(value["files"][0]["filename"], "a.txt");
+}
+
+#[test]
+fn test_git_header_outside_repo_is_omitted() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+    let mut config = config_for(dir.path());
+    config.git_header = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+
+    assert!(!output.contains("Git repository:"));
+    assert!(output.starts_with(">>>> a.txt"));
+}
+
+#[test]
+fn test_file_history_attached_to_processed_files() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "main.rs", "fn main() {}", "Create main");
+    commit_file(dir.path(), "notes.txt", "notes", "Add notes");
+    commit_file(dir.path(), "main.rs", "fn main() { run() }", "Call run");
+    commit_file(
+        dir.path(),
+        "main.rs",
+        "fn main() { run(); }",
+        "Fix semicolon",
+    );
+
+    let mut config = config_for(dir.path());
+    config.file_history = 2;
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    let main = files.iter().find(|f| f.rel_path == "main.rs").unwrap();
+    let subjects: Vec<&str> = main.history.iter().map(|c| c.subject.as_str()).collect();
+    assert_eq!(subjects, vec!["Fix semicolon", "Call run"]);
+    assert_eq!(main.history[0].author, "Test User");
+
+    let notes = files.iter().find(|f| f.rel_path == "notes.txt").unwrap();
+    assert_eq!(notes.history.len(), 1);
+
+    // Comment syntax follows the file's language
+    assert!(output.contains(">>>> main.rs\n// History:\n//   "));
+    assert!(output.contains("Test User: Fix semicolon\n"));
+    assert!(output.contains(">>>> notes.txt\n# History:\n#   "));
+}
+
+#[test]
+fn test_file_history_in_block_comment_languages() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "style.css", "a {}", "Style links */ a {}");
+    commit_file(dir.path(), "index.html", "<p></p>", "Add page --> done");
+    commit_file(dir.path(), "data.xml", "<a/>", "Add data");
+
+    let mut config = config_for(dir.path());
+    config.file_history = 1;
+    let (output, _) = serialize_repo(&config).unwrap();
+
+    // Languages without line comments get block comments, which commit
+    // text can't close early
+    assert!(output.contains(">>>> style.css\n/* History: */\n/*   "));
+    assert!(output.contains("Test User: Style links * / a {} */\na {}"));
+    assert!(output.contains(">>>> index.html\n<!-- History: -->\n<!--   "));
+    assert!(output.contains("Test User: Add page - -> done -->\n<p></p>"));
+    assert!(output.contains(">>>> data.xml\n<!-- History: -->\n"));
+}
+
+#[test]
+fn test_file_history_for_subdirectory_input() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    fs::create_dir(dir.path().join("src")).unwrap();
+    commit_file(dir.path(), "src/lib.rs", "pub fn a() {}", "Add lib");
+
+    let mut config = config_for(&dir.path().join("src"));
+    config.file_history = 3;
+    let (_, files) = serialize_repo(&config).unwrap();
+
+    assert_eq!(files[0].rel_path, "lib.rs");
+    assert_eq!(files[0].history.len(), 1);
+    assert_eq!(files[0].history[0].subject, "Add lib");
+}
+
+#[test]
+fn test_file_history_in_json_output() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "hello", "Add greeting");
+
+    let mut config = config_for(dir.path());
+    config.file_history = 1;
+    config.json = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value[0]["content"], "hello");
+    assert_eq!(value[0]["history"][0]["subject"], "Add greeting");
+    assert_eq!(value[0]["history"][0]["sha"].as_str().unwrap().len(), 7);
+}
+
+#[test]
+fn test_file_history_disabled_by_default() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "hello", "Add greeting");
+
+    let (output, files) = serialize_repo(&config_for(dir.path())).unwrap();
+    assert!(files[0].history.is_empty());
+    assert!(!output.contains("History:"));
+}
+
+#[test]
+fn test_format_age() {
+    assert_eq!(format_age(5), "now");
+    assert_eq!(format_age(120), "2m");
+    assert_eq!(format_age(3 * 3600), "3h");
+    assert_eq!(format_age(4 * 86400), "4d");
+    assert_eq!(format_age(65 * 86400), "2mo");
+    assert_eq!(format_age(800 * 86400), "2y");
+    assert_eq!(format_age(-10), "now");
+}
+
+#[test]
+fn test_format_content_with_blame() {
+    let tags = vec!["2d alice".to_string(), "5y bob  ".to_string()];
+    assert_eq!(
+        format_content_with_blame("one\ntwo", &tags),
+        "2d alice | one\n5y bob   | two"
+    );
+    assert_eq!(format_content_with_blame("one", &[]), "one");
+}
+
+#[test]
+fn test_blame_tags_committed_and_uncommitted_lines() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "first\nsecond\n", "Add a");
+    fs::write(dir.path().join("a.txt"), "first\nsecond\nthird\n").unwrap();
+
+    let mut config = config_for(dir.path());
+    config.blame = true;
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    assert_eq!(files[0].blame.len(), 3);
+    assert!(files[0].blame[0].contains("now Test"));
+    assert!(files[0].blame[2].contains("uncommitted"));
+    assert!(output.contains("now Test        | first\n"));
+    assert!(output.contains("  - uncommitted | third"));
+}
+
+#[test]
+fn test_blame_combined_with_line_numbers() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "first\nsecond", "Add a");
+
+    let mut config = config_for(dir.path());
+    config.blame = true;
+    config.line_numbers = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+
+    assert!(output.contains("  1 | now Test | first\n"));
+    assert!(output.contains("  2 | now Test | second"));
+}
+
+#[test]
+fn test_blame_identical_files_at_different_paths() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file_as(
+        dir.path(),
+        "b.txt",
+        "same\n",
+        "Bob <bob@example.com>",
+        "2020-01-01T00:00:00Z",
+    );
+    commit_file(dir.path(), "a.txt", "same\n", "Add a");
+
+    let mut config = config_for(dir.path());
+    config.blame = true;
+    let (_, files) = serialize_repo(&config).unwrap();
+
+    let tag = |path: &str| {
+        files
+            .iter()
+            .find(|f| f.rel_path == path)
+            .map(|f| f.blame[0].clone())
+            .unwrap()
+    };
+    assert!(tag("a.txt").contains("now Test"));
+    assert!(tag("b.txt").ends_with("y Bob"));
+}
+
+#[test]
+fn test_blame_cache_shared_across_calls() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    commit_file(dir.path(), "sub/a.txt", "same\n", "Add a");
+    commit_file(dir.path(), "b.txt", "same\n", "Add b");
+
+    let cache = BlameCache::default();
+    let mut from_root = vec![file("sub/a.txt", "same\n"), file("b.txt", "same\n")];
+    attach_blame(&mut from_root, dir.path(), &cache);
+    assert_eq!(cache.computed(), 2);
+
+    // The same file reached from another input path reuses its blame
+    let mut from_sub = vec![file("a.txt", "same\n")];
+    attach_blame(&mut from_sub, &dir.path().join("sub"), &cache);
+    assert_eq!(cache.computed(), 2);
+    assert_eq!(from_sub[0].blame, from_root[0].blame);
+
+    // Changed content is blamed again
+    let mut changed = vec![file("b.txt", "other\n")];
+    attach_blame(&mut changed, dir.path(), &cache);
+    assert_eq!(cache.computed(), 3);
+    assert!(changed[0].blame[0].contains("uncommitted"));
+}
+
+#[test]
+fn test_blame_outside_repo_leaves_content_unchanged() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "first").unwrap();
+
+    let mut config = config_for(dir.path());
+    config.blame = true;
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    assert!(files[0].blame.is_empty());
+    assert!(output.contains(">>>> a.txt\nfirst"));
+}
+
+#[test]
+fn test_git_status_markers_in_tree_and_headers() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "kept.txt", "kept", "Add kept");
+    commit_file(dir.path(), "changed.txt", "old", "Add changed");
+    commit_file(dir.path(), "gone.txt", "gone", "Add gone");
+    fs::write(dir.path().join("changed.txt"), "new").unwrap();
+    fs::write(dir.path().join("fresh.txt"), "fresh").unwrap();
+    fs::remove_file(dir.path().join("gone.txt")).unwrap();
+
+    let mut config = config_for(dir.path());
+    config.git_status = true;
+    config.tree_header = true;
+    config.output_template = Some(">>>> FILE_PATH [FILE_STATUS]\nFILE_CONTENT".to_string());
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    let changed = files.iter().find(|f| f.rel_path == "changed.txt").unwrap();
+    assert_eq!(changed.git_status.as_deref(), Some("M"));
+    let kept = files.iter().find(|f| f.rel_path == "kept.txt").unwrap();
+    assert!(kept.git_status.is_none());
+
+    assert!(output.contains("├── changed.txt [M]\n"));
+    assert!(output.contains("├── fresh.txt [??]\n"));
+    assert!(output.contains("├── gone.txt [D]\n"));
+    assert!(output.contains("└── kept.txt\n"));
+    assert!(output.contains(">>>> changed.txt [M]\nnew"));
+    assert!(output.contains(">>>> kept.txt []\nkept"));
+}
+
+#[test]
+fn test_git_status_in_json_output() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "a.txt", "a", "Add a");
+    fs::write(dir.path().join("b.txt"), "b").unwrap();
+
+    let mut config = config_for(dir.path());
+    config.git_status = true;
+    config.json = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let entries = value.as_array().unwrap();
+    let a = entries.iter().find(|e| e["filename"] == "a.txt").unwrap();
+    let b = entries.iter().find(|e| e["filename"] == "b.txt").unwrap();
+    assert!(a["status"].is_null());
+    assert_eq!(b["status"], "??");
+}
+
+fn ticket_repo(repo_path: &Path) {
+    init_repo(repo_path);
+    commit_file(repo_path, "a.txt", "a", "ABC-1: add a");
+    commit_file(repo_path, "b.txt", "b", "Unrelated change");
+    commit_file(repo_path, "c.txt", "c", "Fix c\n\nRefs ABC-1");
+    commit_file(repo_path, "d.txt", "d", "ABC-12: add d");
+}
+
+#[test]
+fn test_commits_matching_includes_only_touched_files() {
+    let dir = tempdir().unwrap();
+    ticket_repo(dir.path());
+
+    let mut config = config_for(dir.path());
+    config.commits_matching = Some(r"ABC-1\b".to_string());
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    let mut paths: Vec<&str> = files.iter().map(|f| f.rel_path.as_str()).collect();
+    paths.sort();
+    // Matches the subject and the body, but not ABC-12
+    assert_eq!(paths, vec!["a.txt", "c.txt"]);
+    assert!(!output.contains(">>>> b.txt"));
+}
+
+#[test]
+fn test_commits_matching_with_boost_keeps_all_files() {
+    let dir = tempdir().unwrap();
+    ticket_repo(dir.path());
+
+    let mut config = config_for(dir.path());
+    config.commits_matching = Some("ABC-12".to_string());
+    config.commits_boost = Some(500);
+    let (_, files) = serialize_repo(&config).unwrap();
+
+    assert_eq!(files.len(), 4);
+    let boosted = files.iter().find(|f| f.rel_path == "d.txt").unwrap();
+    let other = files.iter().find(|f| f.rel_path == "b.txt").unwrap();
+    assert!(boosted.priority >= other.priority + 500);
+    // Higher priority files come last
+    assert_eq!(files.last().unwrap().rel_path, "d.txt");
+}
+
+#[test]
+fn test_commits_matching_since_excludes_older_commits() {
+    let dir = tempdir().unwrap();
+    ticket_repo(dir.path());
+
+    let mut config = config_for(dir.path());
+    config.commits_matching = Some("ABC".to_string());
+    config.since = Some("2999-01-01".to_string());
+    let (_, files) = serialize_repo(&config).unwrap();
+    assert!(files.is_empty());
+
+    config.since = Some("1d".to_string());
+    let (_, files) = serialize_repo(&config).unwrap();
+    assert_eq!(files.len(), 3);
+}
+
+#[test]
+fn test_parse_since() {
+    assert_eq!(parse_since("1970-01-02", 0).unwrap(), 86400);
+    assert_eq!(parse_since("2d", 1_000_000).unwrap(), 1_000_000 - 2 * 86400);
+    assert_eq!(parse_since("1w", 1_000_000).unwrap(), 1_000_000 - 7 * 86400);
+    assert_eq!(parse_since("1mo", 0).unwrap(), -30 * 86400);
+    assert_eq!(parse_since("1y", 0).unwrap(), -365 * 86400);
+    assert!(parse_since("yesterday", 0).is_err());
+    assert!(parse_since("2024-13-01", 0).is_err());
+    assert!(parse_since("5h", 0).is_err());
+    // Ages too large to subtract are errors, not overflows
+    assert!(parse_since("99999999999999999y", 0).is_err());
+    assert!(parse_since("106751991167300d", i64::MIN).is_err());
+
+    let config = YekConfig {
+        since: Some("99999999999999999y".to_string()),
+        ..Default::default()
+    };
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_commits_matching_since_across_merged_branch() {
+    let dir = tempdir().unwrap();
+    init_repo(dir.path());
+    commit_file(dir.path(), "base.txt", "base", "Add base");
+    git(dir.path(), &["checkout", "-b", "feature"]);
+    commit_file(dir.path(), "f.txt", "f", "ABC-7: add f");
+    // A commit from a machine with a wrong clock, older than its parent
+    commit_file_as(
+        dir.path(),
+        "g.txt",
+        "g",
+        "Test User <test@example.com>",
+        "2020-01-01T00:00:00Z",
+    );
+    git(dir.path(), &["checkout", "main"]);
+    commit_file(dir.path(), "h.txt", "h", "Add h");
+    git(
+        dir.path(),
+        &["merge", "--no-ff", "feature", "-m", "Merge feature"],
+    );
+
+    let mut config = config_for(dir.path());
+    config.commits_matching = Some("ABC-7".to_string());
+    config.since = Some("1d".to_string());
+    let (_, files) = serialize_repo(&config).unwrap();
+
+    let paths: Vec<&str> = files.iter().map(|f| f.rel_path.as_str()).collect();
+    assert_eq!(paths, vec!["f.txt"]);
+}
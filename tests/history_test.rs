@@ -0,0 +1,110 @@
+mod common;
+
+use common::{commit_file, git, init_repo};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+use yek::{config::YekConfig, history::collect_file_versions, serialize_repo};
+
+/// Repository where api.rs has four distinct versions, plus one unrelated commit
+fn api_repo(repo_path: &Path) {
+    init_repo(repo_path);
+    commit_file(repo_path, "api.rs", "fn get() {}\n", "v1");
+    commit_file(repo_path, "api.rs", "fn get(id: u32) {}\n", "v2");
+    commit_file(repo_path, "other.txt", "x", "Unrelated");
+    commit_file(repo_path, "api.rs", "fn get(id: u64) {}\n", "v3");
+    commit_file(
+        repo_path,
+        "api.rs",
+        "fn get(id: u64) {}\nfn put() {}\n",
+        "v4",
+    );
+}
+
+fn history_config(path: &Path, versions: usize) -> YekConfig {
+    YekConfig {
+        history: Some(path.to_string_lossy().to_string()),
+        versions,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_collect_file_versions_oldest_first() {
+    let dir = tempdir().unwrap();
+    api_repo(dir.path());
+
+    let (repo_path, versions) = collect_file_versions(&dir.path().join("api.rs"), 3).unwrap();
+    assert_eq!(repo_path, "api.rs");
+    let subjects: Vec<&str> = versions.iter().map(|v| v.commit.subject.as_str()).collect();
+    // The unrelated commit does not create a new version
+    assert_eq!(subjects, vec!["v2", "v3", "v4"]);
+    assert_eq!(versions[0].content, "fn get(id: u32) {}\n");
+}
+
+#[test]
+fn test_history_oldest_in_full_then_diffs() {
+    let dir = tempdir().unwrap();
+    api_repo(dir.path());
+
+    let (output, files) = serialize_repo(&history_config(&dir.path().join("api.rs"), 2)).unwrap();
+
+    assert!(output.starts_with(">>>> api.rs @ "));
+    assert!(output.contains("Test User: v3\nfn get(id: u64) {}\n"));
+    assert!(output.contains("Test User: v4 (diff from "));
+    assert!(output.contains("+++ b/api.rs\n"));
+    assert!(output.contains("+fn put() {}\n"));
+    assert!(!output.contains("v2"));
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].content, "fn get(id: u64) {}\nfn put() {}\n");
+    assert_eq!(files[0].history.len(), 2);
+}
+
+#[test]
+fn test_history_drops_oldest_versions_to_fit_budget() {
+    let dir = tempdir().unwrap();
+    let big = "line\n".repeat(200);
+    git(dir.path(), &["init", "-b", "main"]);
+    git(dir.path(), &["config", "user.name", "Test User"]);
+    git(dir.path(), &["config", "user.email", "test@example.com"]);
+    commit_file(dir.path(), "a.txt", &big, "big");
+    commit_file(dir.path(), "a.txt", "small\n", "small");
+    commit_file(dir.path(), "a.txt", "smaller\n", "smaller");
+
+    let mut config = history_config(&dir.path().join("a.txt"), 3);
+    config.max_size = "500".to_string();
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    assert!(!output.contains(": big"));
+    assert!(output.contains(": small\nsmall\n"));
+    assert!(output.contains("+smaller"));
+    assert_eq!(files[0].history.len(), 2);
+}
+
+#[test]
+fn test_history_json_output() {
+    let dir = tempdir().unwrap();
+    api_repo(dir.path());
+
+    let mut config = history_config(&dir.path().join("api.rs"), 2);
+    config.json = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value[0]["commit"]["subject"], "v3");
+    assert_eq!(value[0]["content"], "fn get(id: u64) {}\n");
+    assert_eq!(value[1]["commit"]["subject"], "v4");
+    assert!(value[1]["diff"].as_str().unwrap().contains("+fn put() {}"));
+}
+
+#[test]
+fn test_history_outside_repo_is_an_error() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+    let err = serialize_repo(&history_config(&dir.path().join("a.txt"), 2))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("history"));
+}
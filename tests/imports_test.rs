@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+use yek::{config::YekConfig, imports::build_import_graph, serialize_repo};
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn graph(files: &[(&str, &str)]) -> HashMap<String, Vec<String>> {
+    let known: HashMap<&str, &str> = files.iter().copied().collect();
+    build_import_graph(&known)
+}
+
+#[test]
+fn test_rust_mod_and_use_imports() {
+    let g = graph(&[
+        ("src/lib.rs", "pub mod a;\nmod b;\n"),
+        (
+            "src/a.rs",
+            "use crate::b::{helper, Thing as T};\nmod inner;\n",
+        ),
+        ("src/a/inner.rs", "use super::super::c::run;\n"),
+        ("src/b/mod.rs", "use self::deep::X;\nmod deep;\n"),
+        ("src/b/deep.rs", ""),
+        ("src/c.rs", "use std::io;\n"),
+    ]);
+    assert_eq!(g["src/lib.rs"], vec!["src/a.rs", "src/b/mod.rs"]);
+    assert_eq!(g["src/a.rs"], vec!["src/a/inner.rs", "src/b/mod.rs"]);
+    assert_eq!(g["src/a/inner.rs"], vec!["src/c.rs"]);
+    assert_eq!(g["src/b/mod.rs"], vec!["src/b/deep.rs"]);
+    assert!(g["src/c.rs"].is_empty());
+}
+
+#[test]
+fn test_js_ts_imports() {
+    let g = graph(&[
+        (
+            "src/app.ts",
+            "import { a } from './a';\nimport b from \"../lib/b.js\";\nimport './side';\nimport React from 'react';\n",
+        ),
+        ("src/a.ts", "const u = require('./utils');\nexport * from './types';\n"),
+        ("src/side.js", "import('./lazy').then(() => {});\n"),
+        ("src/utils/index.js", ""),
+        ("src/types.d.ts", ""),
+        ("src/types.ts", ""),
+        ("src/lazy.jsx", ""),
+        ("lib/b.ts", ""),
+    ]);
+    assert_eq!(g["src/app.ts"], vec!["lib/b.ts", "src/a.ts", "src/side.js"]);
+    assert_eq!(g["src/a.ts"], vec!["src/types.ts", "src/utils/index.js"]);
+    assert_eq!(g["src/side.js"], vec!["src/lazy.jsx"]);
+}
+
+#[test]
+fn test_python_imports() {
+    let g = graph(&[
+        (
+            "main.py",
+            "import pkg.models as m, os\nfrom pkg import views\n",
+        ),
+        ("pkg/__init__.py", ""),
+        (
+            "pkg/models.py",
+            "from .util import helper\nfrom . import views\n",
+        ),
+        ("pkg/views.py", "from ..main import app\n"),
+        ("pkg/util.py", ""),
+    ]);
+    assert_eq!(
+        g["main.py"],
+        vec!["pkg/__init__.py", "pkg/models.py", "pkg/views.py"]
+    );
+    assert_eq!(g["pkg/models.py"], vec!["pkg/util.py", "pkg/views.py"]);
+    assert_eq!(g["pkg/views.py"], vec!["main.py"]);
+}
+
+#[test]
+fn test_go_imports() {
+    let g = graph(&[
+        ("go.mod", "module example.com/app\n\ngo 1.21\n"),
+        (
+            "main.go",
+            "package main\n\nimport (\n\t\"fmt\"\n\tstore \"example.com/app/internal/store\"\n)\n",
+        ),
+        (
+            "internal/store/store.go",
+            "package store\nimport \"example.com/app/util\"\n",
+        ),
+        ("internal/store/store_test.go", "package store\n"),
+        ("util/util.go", "package util\n"),
+    ]);
+    assert_eq!(g["main.go"], vec!["internal/store/store.go"]);
+    assert_eq!(g["internal/store/store.go"], vec!["util/util.go"]);
+}
+
+fn chain_project(root: &Path) {
+    write(root, "src/main.rs", "mod a;\nfn main() {}\n");
+    write(root, "src/a.rs", "use crate::b::run;\n");
+    write(root, "src/b.rs", "use crate::c::go;\n");
+    write(root, "src/c.rs", "pub fn go() {}\n");
+    write(root, "src/unrelated.rs", "pub fn x() {}\n");
+    write(root, "README.md", "# readme\n");
+}
+
+#[test]
+fn test_follow_imports_with_depth_and_decay() {
+    let dir = tempdir().unwrap();
+    chain_project(dir.path());
+
+    let config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        follow_imports: vec!["src/main.rs".to_string()],
+        import_depth: 2,
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    let paths: Vec<&str> = files.iter().map(|f| f.rel_path.as_str()).collect();
+    // Lowest priority first: the farthest import comes first, the seed last
+    assert_eq!(paths, vec!["src/b.rs", "src/a.rs", "src/main.rs"]);
+    assert!(files[0].priority < files[1].priority);
+    assert!(files[1].priority < files[2].priority);
+    assert!(!output.contains("unrelated.rs"));
+    assert!(!output.contains("README.md"));
+}
+
+#[test]
+fn test_follow_imports_reverse_direction() {
+    let dir = tempdir().unwrap();
+    chain_project(dir.path());
+
+    let config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        follow_imports: vec![dir.path().join("src/b.rs").to_string_lossy().to_string()],
+        import_depth: 1,
+        reverse_imports: true,
+        ..Default::default()
+    };
+    let (_, files) = serialize_repo(&config).unwrap();
+
+    let mut paths: Vec<&str> = files.iter().map(|f| f.rel_path.as_str()).collect();
+    paths.sort();
+    // b imports c; a imports b
+    assert_eq!(paths, vec!["src/a.rs", "src/b.rs", "src/c.rs"]);
+}
+
+#[test]
+fn test_rust_use_of_crate_root_items() {
+    let g = graph(&[
+        ("src/lib.rs", "pub mod a;\npub struct Config;\n"),
+        (
+            "src/a.rs",
+            "use crate::Config;\nuse super::{helper, Config as C};\n",
+        ),
+        ("src/bin/tool/main.rs", "mod cli;\n"),
+        ("src/bin/tool/cli.rs", "use crate::VERSION;\n"),
+    ]);
+    assert_eq!(g["src/a.rs"], vec!["src/lib.rs"]);
+    assert_eq!(g["src/bin/tool/cli.rs"], vec!["src/bin/tool/main.rs"]);
+}
+
+#[test]
+fn test_follow_imports_unknown_seed_keeps_all_files() {
+    let dir = tempdir().unwrap();
+    chain_project(dir.path());
+
+    let config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        follow_imports: vec!["src/missing.rs".to_string()],
+        ..Default::default()
+    };
+    let (_, files) = serialize_repo(&config).unwrap();
+    assert_eq!(files.len(), 6);
+}
+
+#[test]
+fn test_follow_imports_input_path_without_seeds() {
+    let dir = tempdir().unwrap();
+    chain_project(dir.path());
+    write(dir.path(), "docs/guide.md", "# guide\n");
+
+    // The seed is only in src/; docs/ is kept whole
+    let config = YekConfig {
+        input_paths: vec![
+            dir.path().join("src").to_string_lossy().to_string(),
+            dir.path().join("docs").to_string_lossy().to_string(),
+        ],
+        follow_imports: vec![dir.path().join("src/b.rs").to_string_lossy().to_string()],
+        import_depth: 1,
+        ..Default::default()
+    };
+    let (_, files) = serialize_repo(&config).unwrap();
+
+    let mut paths: Vec<&str> = files.iter().map(|f| f.rel_path.as_str()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["b.rs", "c.rs", "guide.md"]);
+}
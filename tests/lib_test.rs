@@ -730,6 +730,28 @@ mod lib_tests {
         );
     }
 
+    #[test]
+    fn test_size_budget_keeps_highest_priority_files() {
+        let config = YekConfig {
+            max_size: "35B".to_string(),
+            ..Default::default()
+        };
+        let files = vec![
+            ProcessedFile::new("low.txt".to_string(), "low-------".to_string(), 0, 0),
+            ProcessedFile::new("mid.txt".to_string(), "mid-------".to_string(), 10, 1),
+            ProcessedFile::new("big.txt".to_string(), "big".repeat(20), 50, 2),
+            ProcessedFile::new("top.txt".to_string(), "top-------".to_string(), 100, 3),
+        ];
+        let output = concat_files(&files, &config).unwrap();
+        // The budget is filled from the highest priority down; a file that
+        // doesn't fit is skipped and lower priority files still fill the rest.
+        // Included files are output lowest priority first.
+        assert_eq!(
+            output,
+            ">>>> low.txt\nlow-------\n>>>> mid.txt\nmid-------\n>>>> top.txt\ntop-------"
+        );
+    }
+
     #[test]
     fn test_parse_token_limit() {
         assert_eq!(parse_token_limit("1000").unwrap(), 1000);
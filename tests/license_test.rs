@@ -0,0 +1,84 @@
+mod common;
+
+use common::{config_for, file, project};
+use yek::{
+    config::YekConfig,
+    license::{collect_license_headers, strip_license_headers},
+    serialize_repo,
+};
+
+fn apache_header(year: &str) -> String {
+    format!(
+        "// Copyright {} Example Corp.\n//\n// Licensed under the Apache License, Version 2.0\n// SPDX-License-Identifier: Apache-2.0\n\n",
+        year
+    )
+}
+
+#[test]
+fn test_strip_repeated_license_headers() {
+    let mut files = vec![
+        file("a.rs", &format!("{}fn a() {{}}\n", apache_header("2021"))),
+        file(
+            "b.rs",
+            &format!("{}//! Docs\nfn b() {{}}\n", apache_header("2019-2024")),
+        ),
+        file("c.rs", &format!("{}fn c() {{}}\n", apache_header("2023"))),
+        file("d.rs", "// Just a comment\nfn d() {}\n"),
+    ];
+    assert_eq!(strip_license_headers(&mut files), 3);
+
+    assert_eq!(files[0].content, "fn a() {}\n");
+    assert_eq!(files[0].size_bytes, files[0].content.len());
+    assert_eq!(files[1].content, "//! Docs\nfn b() {}\n");
+    assert_eq!(files[3].content, "// Just a comment\nfn d() {}\n");
+    assert!(files[3].license_header.is_none());
+
+    let headers = collect_license_headers(&files);
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers[0].files, 3);
+    assert!(headers[0]
+        .text
+        .starts_with("// Copyright 2021 Example Corp."));
+}
+
+#[test]
+fn test_license_headers_need_repeats() {
+    let mut files = vec![
+        file(
+            "a.py",
+            "#!/usr/bin/env python\n# Copyright 2024 Me\n\nprint(1)\n",
+        ),
+        file("b.py", "# Copyright 2024 Me\n\nprint(2)\n"),
+        file("c.py", "# Some module\n\nprint(3)\n"),
+    ];
+    assert_eq!(strip_license_headers(&mut files), 0);
+    assert!(files[0].content.contains("Copyright"));
+}
+
+#[test]
+fn test_strip_license_headers_output() {
+    let source = |name: &str| format!("{}pub fn {}() {{}}\n", apache_header("2024"), name);
+    let (a, b, c) = (source("a"), source("b"), source("c"));
+    let dir = project(&[("a.rs", &a), ("b.rs", &b), ("c.rs", &c)]);
+
+    let mut config = YekConfig {
+        strip_license_headers: true,
+        ..config_for(dir.path())
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output
+        .starts_with("License header (stripped from 3 files):\n// Copyright 2024 Example Corp.\n"));
+    assert_eq!(output.matches("Licensed under").count(), 1);
+    assert!(output.contains(">>>> a.rs\npub fn a() {}\n"));
+
+    config.json = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value["license_headers"][0]["files"], 3);
+    assert_eq!(value["files"].as_array().unwrap().len(), 3);
+
+    // Opt-in: headers are kept by default
+    config.strip_license_headers = false;
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert_eq!(output.matches("Licensed under").count(), 3);
+}
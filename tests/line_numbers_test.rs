@@ -1,6 +1,6 @@
 use std::fs;
 use tempfile::tempdir;
-use yek::{config::YekConfig, serialize_repo};
+use yek::{config::YekConfig, selection::parse_line_range, serialize_repo};
 
 #[cfg(test)]
 #[allow(clippy::field_reassign_with_default)]
@@ -146,4 +146,63 @@ mod line_numbers_tests {
         assert!(output.contains("  1 | line 1"));
         assert!(output.contains("  2 | line 2"));
     }
+
+    #[test]
+    fn test_line_range_input_path() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let content: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        fs::write(&file_path, content).unwrap();
+        let file = file_path.to_string_lossy().to_string();
+
+        let mut config = YekConfig::default();
+        config.input_paths = vec![format!("{}:9-11", file)];
+        let (output, files) = serialize_repo(&config).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(output.contains(">>>> test.txt (lines 9-11)\nline 9\nline 10\nline 11\n"));
+        assert!(!output.contains("line 8\n"));
+        assert!(!output.contains("line 12"));
+
+        // START+COUNT, with original line numbers
+        config.input_paths = vec![format!("{}:10+2", file)];
+        config.line_numbers = true;
+        let (output, _) = serialize_repo(&config).unwrap();
+        assert!(output.contains(">>>> test.txt (lines 10-11)\n 10 | line 10\n 11 | line 11"));
+
+        // Ranges past the end are clamped; ranges outside the file drop it
+        config.input_paths = vec![format!("{}:19-40", file)];
+        let (output, _) = serialize_repo(&config).unwrap();
+        assert!(output.contains(">>>> test.txt (lines 19-20)\n 19 | line 19\n 20 | line 20"));
+        config.input_paths = vec![format!("{}:30-40", file)];
+        let (_, files) = serialize_repo(&config).unwrap();
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    fn test_parse_line_range() {
+        assert_eq!(parse_line_range("9-11"), Some((9, 11)));
+        assert_eq!(parse_line_range("10+2"), Some((10, 11)));
+        assert_eq!(parse_line_range("40-10"), None);
+        assert_eq!(parse_line_range("0-3"), None);
+        // Counts that would overflow are rejected, not wrapped
+        assert_eq!(parse_line_range(&format!("2+{}", usize::MAX)), None);
+        assert_eq!(parse_line_range(&format!("1+{}", usize::MAX)), None);
+    }
+
+    #[test]
+    fn test_line_range_input_path_json() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        fs::write(&file_path, "a\nb\nc\nd\n").unwrap();
+
+        let mut config = YekConfig::default();
+        config.input_paths = vec![format!("{}:2-3", file_path.to_string_lossy())];
+        config.json = true;
+        let (output, _) = serialize_repo(&config).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value[0]["filename"], "test.txt");
+        assert_eq!(value[0]["selection"], ":2-3");
+        assert_eq!(value[0]["lines"], serde_json::json!([2, 3]));
+        assert_eq!(value[0]["content"], "b\nc\n");
+    }
 }
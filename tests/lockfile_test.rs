@@ -0,0 +1,154 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{config::YekConfig, lockfile::summarize_lockfile, serialize_repo};
+
+const CARGO_LOCK: &str = r#"# This file is automatically @generated by Cargo.
+version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "serde",
+ "syn 2.0.100",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.219"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "syn 1.0.109",
+]
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.100"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "unicode-ident"
+version = "1.0.18"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+#[test]
+fn test_summarize_cargo_lock() {
+    let summary = summarize_lockfile("Cargo.lock", CARGO_LOCK, None).unwrap();
+    assert_eq!(
+        summary,
+        "Cargo.lock summary: 2 direct and 1 transitive packages\n\
+         direct:\n  serde 1.0.219\n  syn 1.0.109, 2.0.100\n\
+         transitive:\n  unicode-ident 1.0.18\n"
+    );
+    assert!(summarize_lockfile("Cargo.lock", "not toml [", None).is_none());
+    assert!(summarize_lockfile("Cargo.toml", CARGO_LOCK, None).is_none());
+}
+
+#[test]
+fn test_summarize_npm_and_yarn_lockfiles() {
+    let package_lock = r#"{
+      "lockfileVersion": 3,
+      "packages": {
+        "": { "dependencies": { "react": "^18.0.0" }, "devDependencies": { "@types/react": "^18" } },
+        "node_modules/react": { "version": "18.2.0", "dependencies": { "loose-envify": "^1.1.0" } },
+        "node_modules/@types/react": { "version": "18.2.1" },
+        "node_modules/loose-envify": { "version": "1.4.0" }
+      }
+    }"#;
+    let summary = summarize_lockfile("web/package-lock.json", package_lock, None).unwrap();
+    assert!(summary.starts_with("package-lock.json summary: 2 direct and 1 transitive"));
+    assert!(summary.contains("direct:\n  @types/react 18.2.1\n  react 18.2.0\n"));
+    assert!(summary.contains("transitive:\n  loose-envify 1.4.0\n"));
+
+    let yarn_lock = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/code-frame@^7.0.0", "@babel/code-frame@^7.22.0":
+  version "7.22.13"
+  dependencies:
+    "@babel/highlight" "^7.22.13"
+
+"@babel/highlight@^7.22.13":
+  version "7.22.20"
+
+lodash@^4.17.21:
+  version "4.17.21"
+"#;
+    // Without package.json, packages nothing depends on count as direct
+    let summary = summarize_lockfile("yarn.lock", yarn_lock, None).unwrap();
+    assert!(summary.contains("direct:\n  @babel/code-frame 7.22.13\n  lodash 4.17.21\n"));
+    assert!(summary.contains("transitive:\n  @babel/highlight 7.22.20\n"));
+
+    let manifest = r#"{ "dependencies": { "lodash": "^4.17.21" } }"#;
+    let summary = summarize_lockfile("yarn.lock", yarn_lock, Some(manifest)).unwrap();
+    assert!(summary.starts_with("yarn.lock summary: 1 direct and 2 transitive"));
+}
+
+#[test]
+fn test_summarize_poetry_lock_and_go_sum() {
+    let poetry_lock = r#"
+[[package]]
+name = "Requests"
+version = "2.31.0"
+
+[package.dependencies]
+urllib3 = ">=1.21.1,<3"
+
+[[package]]
+name = "urllib3"
+version = "2.0.7"
+"#;
+    let pyproject = "[project]\ndependencies = [\"requests[socks]>=2.0\"]\n";
+    let summary = summarize_lockfile("poetry.lock", poetry_lock, Some(pyproject)).unwrap();
+    assert_eq!(
+        summary,
+        "poetry.lock summary: 1 direct and 1 transitive packages\n\
+         direct:\n  requests 2.31.0\n\
+         transitive:\n  urllib3 2.0.7\n"
+    );
+
+    let go_sum = "github.com/a/b v1.0.0 h1:abc=\n\
+                  github.com/a/b v1.0.0/go.mod h1:def=\n\
+                  golang.org/x/text v0.3.0/go.mod h1:ghi=\n";
+    let summary = summarize_lockfile("go.sum", go_sum, None).unwrap();
+    assert_eq!(
+        summary,
+        "go.sum summary: 1 packages\n  github.com/a/b v1.0.0\n"
+    );
+
+    let go_mod = "module example.com/app\n\nrequire github.com/a/b v1.0.0\n\n\
+                  require (\n\tgolang.org/x/text v0.14.0 // indirect\n)\n";
+    let summary = summarize_lockfile("go.sum", go_sum, Some(go_mod)).unwrap();
+    assert!(summary.contains("direct:\n  github.com/a/b v1.0.0\n"));
+    assert!(summary.contains("transitive:\n  golang.org/x/text v0.14.0\n"));
+}
+
+#[test]
+fn test_summarize_lockfiles_option() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.lock"), CARGO_LOCK).unwrap();
+    fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+
+    let mut config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        summarize_lockfiles: true,
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> Cargo.lock\nCargo.lock summary: 2 direct"));
+    assert!(!output.contains("registry+https"));
+    let lockfile = files.iter().find(|f| f.rel_path == "Cargo.lock").unwrap();
+    assert_eq!(lockfile.size_bytes, lockfile.content.len());
+
+    // Without the option the lockfile is skipped as a generated file
+    config.summarize_lockfiles = false;
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(!output.contains("Cargo.lock"));
+}
@@ -427,6 +427,7 @@ fn test_output_name_only_no_output_dir() {
 
     let cmd = Command::cargo_bin("yek")
         .expect("Binary 'yek' not found")
+        .current_dir(temp_dir.path())
         .arg(temp_dir.path())
         .arg("--output-name")
         .arg("standalone-output.txt")
@@ -434,8 +435,8 @@ fn test_output_name_only_no_output_dir() {
 
     cmd.success();
 
-    // Check that the output file was created in the temp directory (fallback behavior)
-    // Note: when no output_dir is specified and not streaming, it should fall back to temp dir
+    // Without an output_dir, the output name is relative to the working directory
+    assert!(temp_dir.path().join("standalone-output.txt").exists());
 }
 
 #[test]
@@ -0,0 +1,115 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{config::YekConfig, lang::Language, minify::minify, serialize_repo};
+
+const RUST: &str = r##"//! Crate docs
+
+/// Adds numbers   
+pub fn add(a: i32, b: i32) -> i32 {
+    // plain comment
+    let url = "http://example.com"; // trailing
+    let raw = r#"/* not a comment */"#;
+
+
+    /* block
+       comment */
+    let c = '"';
+    a + b
+}
+"##;
+
+#[test]
+fn test_minify_level_1() {
+    let minified = minify(RUST, Language::Rust, 1, false);
+    assert!(!minified.contains("\n\n"));
+    assert!(minified.contains("/// Adds numbers\npub fn add"));
+    assert!(minified.contains("// plain comment"));
+    assert!(minified.ends_with("}\n"));
+}
+
+#[test]
+fn test_minify_level_2_strips_comments_outside_strings() {
+    let minified = minify(RUST, Language::Rust, 2, false);
+    assert_eq!(
+        minified,
+        "pub fn add(a: i32, b: i32) -> i32 {\n    let url = \"http://example.com\";\n    let raw = r#\"/* not a comment */\"#;\n    let c = '\"';\n    a + b\n}\n"
+    );
+
+    let minified = minify(RUST, Language::Rust, 2, true);
+    assert!(minified.starts_with("//! Crate docs\n/// Adds numbers\npub fn add"));
+    assert!(!minified.contains("plain comment"));
+}
+
+#[test]
+fn test_minify_keeps_multiline_strings() {
+    let python = "#!/usr/bin/env python\n# comment\ndef f():\n    \"\"\"Doc   \n\n    # not a comment\n    \"\"\"\n    return '#'  # trailing\n";
+    assert_eq!(
+        minify(python, Language::Python, 2, false),
+        "#!/usr/bin/env python\ndef f():\n    \"\"\"Doc   \n\n    # not a comment\n    \"\"\"\n    return '#'\n"
+    );
+
+    let js = "const t = `a\n\n// b`; // c\n";
+    assert_eq!(
+        minify(js, Language::JavaScript, 2, false),
+        "const t = `a\n\n// b`;\n"
+    );
+
+    let shell = "echo $# 'it''s' # count\n";
+    assert_eq!(
+        minify(shell, Language::Shell, 2, false),
+        "echo $# 'it''s'\n"
+    );
+}
+
+#[test]
+fn test_minify_keeps_regex_literals() {
+    let js = "const s = x.replace(/'/g, \"\"); // quote\nconst url = 'http://x.com'; // c\nconst r = /[/]\\/\\*/.test(a / b / c); // d\n";
+    assert_eq!(
+        minify(js, Language::JavaScript, 2, false),
+        "const s = x.replace(/'/g, \"\");\nconst url = 'http://x.com';\nconst r = /[/]\\/\\*/.test(a / b / c);\n"
+    );
+}
+
+#[test]
+fn test_minify_keeps_shell_heredocs() {
+    let shell = "cat <<EOF > out.txt\n# keep me   \n\nEOF\n# drop me\ncat <<-'END'\n\t# keep me too\n\tEND\necho $((1<<2)) # shift\n";
+    assert_eq!(
+        minify(shell, Language::Shell, 2, false),
+        "cat <<EOF > out.txt\n# keep me   \n\nEOF\ncat <<-'END'\n\t# keep me too\n\tEND\necho $((1<<2))\n"
+    );
+}
+
+#[test]
+fn test_minify_nested_block_comments() {
+    let rust = "/* a /* b */ c */\nfn f() {}\n";
+    assert_eq!(minify(rust, Language::Rust, 2, false), "fn f() {}\n");
+    // C block comments don't nest
+    let c = "/* a /* b */ int x; /* c */\n";
+    assert_eq!(minify(c, Language::C, 2, false), " int x;\n");
+}
+
+#[test]
+fn test_minify_option_updates_size() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), RUST).unwrap();
+    fs::write(dir.path().join("notes.txt"), "a\n\n\nb\n").unwrap();
+
+    let config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        minify: Some(2),
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+    let lib = files.iter().find(|f| f.rel_path == "lib.rs").unwrap();
+    assert_eq!(lib.size_bytes, lib.content.len());
+    assert!(lib.size_bytes < RUST.len());
+    assert!(!output.contains("plain comment"));
+    // Files in unknown languages are left as they are
+    assert!(output.contains("a\n\n\nb\n"));
+
+    let config = YekConfig {
+        minify: Some(3),
+        ..Default::default()
+    };
+    assert!(config.validate().is_err());
+}
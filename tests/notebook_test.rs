@@ -0,0 +1,103 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{category::FileCategory, config::YekConfig, notebook::render_notebook, serialize_repo};
+
+const NOTEBOOK: &str = r##"{
+  "metadata": {
+    "kernelspec": { "display_name": "Python 3", "language": "python", "name": "python3" }
+  },
+  "nbformat": 4,
+  "nbformat_minor": 5,
+  "cells": [
+    { "cell_type": "markdown", "metadata": {}, "source": ["# Analysis\n", "\n", "Load the data."] },
+    {
+      "cell_type": "code",
+      "execution_count": 1,
+      "metadata": {},
+      "source": ["import pandas as pd\n", "df = pd.read_csv(\"data.csv\")\n", "df.head()"],
+      "outputs": [
+        {
+          "output_type": "execute_result",
+          "execution_count": 1,
+          "metadata": {},
+          "data": {
+            "text/plain": ["   a  b\n", "0  1  2\n", "1  3  4\n", "2  5  6"],
+            "text/html": ["<table>...</table>"]
+          }
+        },
+        {
+          "output_type": "display_data",
+          "metadata": {},
+          "data": { "image/png": "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==" }
+        }
+      ]
+    },
+    { "cell_type": "code", "execution_count": null, "metadata": {}, "source": "", "outputs": [] }
+  ]
+}"##;
+
+#[test]
+fn test_render_notebook() {
+    assert_eq!(
+        render_notebook(NOTEBOOK, 0).unwrap(),
+        "# Jupyter notebook (python)\n\
+         \n\
+         # %% [markdown]\n\
+         # # Analysis\n\
+         #\n\
+         # Load the data.\n\
+         \n\
+         # %%\n\
+         import pandas as pd\n\
+         df = pd.read_csv(\"data.csv\")\n\
+         df.head()\n\
+         \n\
+         # %%\n"
+    );
+
+    // Text outputs are kept up to the line limit; images are dropped
+    let rendered = render_notebook(NOTEBOOK, 2).unwrap();
+    assert!(rendered.contains("df.head()\n# Out:\n#    a  b\n# 0  1  2\n# ... (2 more lines)\n"));
+    assert!(!rendered.contains("iVBOR") && !rendered.contains("<table>"));
+
+    assert!(render_notebook("{\"not\": \"a notebook\"}", 0).is_none());
+}
+
+#[test]
+fn test_notebook_language_from_kernel() {
+    let notebook = r#"{
+      "metadata": { "language_info": { "name": "Rust" } },
+      "cells": [{ "cell_type": "markdown", "source": "Notes" }]
+    }"#;
+    assert_eq!(
+        render_notebook(notebook, 0).unwrap(),
+        "// Jupyter notebook (rust)\n\n// %% [markdown]\n// Notes\n"
+    );
+}
+
+#[test]
+fn test_notebook_processing_and_category() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("analysis.ipynb"), NOTEBOOK).unwrap();
+    let tutorial = r##"{
+      "metadata": {},
+      "cells": [
+        { "cell_type": "markdown", "source": "# Tutorial" },
+        { "cell_type": "markdown", "source": "Explanation" },
+        { "cell_type": "code", "source": "print(1)", "outputs": [] }
+      ]
+    }"##;
+    fs::write(dir.path().join("tutorial.ipynb"), tutorial).unwrap();
+
+    let config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> analysis.ipynb\n# Jupyter notebook (python)\n"));
+    assert!(!output.contains("\"cell_type\""));
+
+    let category = |path: &str| files.iter().find(|f| f.rel_path == path).unwrap().category;
+    assert_eq!(category("analysis.ipynb"), FileCategory::Source);
+    assert_eq!(category("tutorial.ipynb"), FileCategory::Documentation);
+}
@@ -0,0 +1,247 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{config::YekConfig, outline::outline, serialize_repo};
+
+#[test]
+fn test_outline_rust() {
+    let source = r##"//! Crate docs
+use std::fmt;
+
+/// A point
+pub struct Point {
+    pub x: i32,
+}
+
+impl Point {
+    /// Create a point
+    pub fn new(x: i32) -> Self {
+        let s = "}{";
+        let c = '}';
+        Self { x }
+    }
+
+    fn label<'a>(&'a self) -> &'a str where Self: Sized {
+        r#"raw } string"#
+    }
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> String { String::from("shape") }
+}
+"##;
+    let expected = r##"//! Crate docs
+use std::fmt;
+
+/// A point
+pub struct Point {
+    pub x: i32,
+}
+
+impl Point {
+    /// Create a point
+    pub fn new(x: i32) -> Self { ... }
+
+    fn label<'a>(&'a self) -> &'a str where Self: Sized { ... }
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> String { ... }
+}
+"##;
+    assert_eq!(outline("src/point.rs", source).unwrap(), expected);
+}
+
+#[test]
+fn test_outline_rust_escaped_char_literals() {
+    let source = r"fn backslash() -> char {
+    '\\'
+}
+
+fn quote() -> char {
+    '\''
+}
+
+fn byte() -> u8 {
+    b'\\'
+}
+
+pub struct After {
+    pub x: i32,
+}
+";
+    let expected = "fn backslash() -> char { ... }\n\nfn quote() -> char { ... }\n\n\
+                    fn byte() -> u8 { ... }\n\npub struct After {\n    pub x: i32,\n}\n";
+    assert_eq!(outline("src/chars.rs", source).unwrap(), expected);
+}
+
+#[test]
+fn test_outline_skips_regex_literals_and_nested_comments() {
+    let js = "function strip(s) {\n    return s.replace(/}/g, \"\");\n}\n\nclass After {}\n";
+    assert_eq!(
+        outline("strip.js", js).unwrap(),
+        "function strip(s) { ... }\n\nclass After {}\n"
+    );
+
+    let rust = "/* outer /* inner */ { */\nfn f() {\n    1\n}\n\nstruct After;\n";
+    assert_eq!(
+        outline("lib.rs", rust).unwrap(),
+        "/* outer /* inner */ { */\nfn f() { ... }\n\nstruct After;\n"
+    );
+}
+
+#[test]
+fn test_outline_typescript() {
+    let source = r#"import { x } from './x';
+
+/** Fetches users */
+export async function fetchUsers(id: string): Promise<User[]> {
+  if (id) { return []; }
+  return `${id}}`;
+}
+
+export class Store extends Base {
+  private items: string[] = [];
+
+  constructor(private readonly api: Api) {
+    super();
+  }
+
+  // Adds an item
+  async add(item: string): Promise<void> {
+    this.items.push(item);
+  }
+}
+
+export const handler = async (req: Request) => {
+  return req;
+};
+
+if (process.env.DEBUG) {
+  console.log("debug");
+}
+"#;
+    let expected = r#"import { x } from './x';
+
+/** Fetches users */
+export async function fetchUsers(id: string): Promise<User[]> { ... }
+
+export class Store extends Base {
+  private items: string[] = [];
+
+  constructor(private readonly api: Api) { ... }
+
+  // Adds an item
+  async add(item: string): Promise<void> { ... }
+}
+
+export const handler = async (req: Request) => { ... };
+
+if (process.env.DEBUG) {
+  console.log("debug");
+}
+"#;
+    assert_eq!(outline("src/store.ts", source).unwrap(), expected);
+}
+
+#[test]
+fn test_outline_go() {
+    let source = "package main\n\n// Server handles requests\ntype Server struct {\n\tAddr string\n}\n\n// Start runs the server\nfunc (s *Server) Start() error {\n\tif s.Addr == \"\" {\n\t\treturn nil\n\t}\n\treturn nil\n}\n";
+    let expected = "package main\n\n// Server handles requests\ntype Server struct {\n\tAddr string\n}\n\n// Start runs the server\nfunc (s *Server) Start() error { ... }\n";
+    assert_eq!(outline("main.go", source).unwrap(), expected);
+}
+
+#[test]
+fn test_outline_python() {
+    let source = r#"import os
+
+class Repo:
+    """A repository."""
+
+    name = "repo"
+
+    def __init__(self, path):
+        self.path = path
+        self.files = []
+
+    @property
+    def size(
+        self,
+    ) -> int:
+        """Number of files.
+
+        Counted lazily.
+        """
+        return len(self.files)
+
+    def short(self): return self.path
+
+
+async def main():
+    await run()
+"#;
+    let expected = r#"import os
+
+class Repo:
+    """A repository."""
+
+    name = "repo"
+
+    def __init__(self, path):
+        ...
+
+    @property
+    def size(
+        self,
+    ) -> int:
+        """Number of files.
+
+        Counted lazily.
+        """
+        ...
+
+    def short(self): return self.path
+
+
+async def main():
+    ...
+"#;
+    assert_eq!(outline("repo.py", source).unwrap(), expected);
+}
+
+#[test]
+fn test_outline_unsupported_language() {
+    assert!(outline("README.md", "# Title").is_none());
+}
+
+#[test]
+fn test_outline_mode_and_threshold() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.rs"), "fn a() {\n    body();\n}\n").unwrap();
+    fs::write(dir.path().join("b.py"), "def b():\n    body()\n").unwrap();
+    fs::write(dir.path().join("notes.txt"), "fn not_code() { body(); }\n").unwrap();
+
+    let mut config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        outline: true,
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> a.rs\nfn a() { ... }\n"));
+    assert!(output.contains(">>>> b.py\ndef b():\n    ...\n"));
+    assert!(output.contains("fn not_code() { body(); }"));
+    let a = files.iter().find(|f| f.rel_path == "a.rs").unwrap();
+    assert_eq!(a.size_bytes, "fn a() { ... }\n".len());
+
+    // Only files below the threshold are outlined
+    config.outline = false;
+    config.priority_rules = vec![yek::priority::PriorityRule {
+        pattern: r"^a\.rs$".to_string(),
+        score: 500,
+    }];
+    config.outline_below = Some(100);
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains("    body();"));
+    assert!(output.contains("def b():\n    ...\n"));
+}
@@ -0,0 +1,84 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{
+    config::YekConfig,
+    relevance::{tokenize, Bm25Index},
+    serialize_repo,
+};
+
+#[test]
+fn test_tokenize_splits_identifiers() {
+    assert_eq!(
+        tokenize("refreshToken"),
+        vec!["refreshtoken", "refresh", "token"]
+    );
+    assert_eq!(
+        tokenize("token_refresh_race"),
+        vec!["tokenrefreshrace", "token", "refresh", "race"]
+    );
+    assert_eq!(
+        tokenize("HTTPServerError"),
+        vec!["httpservererror", "http", "server", "error"]
+    );
+    assert_eq!(
+        tokenize("src/auth-v2.rs"),
+        vec!["src", "auth", "v2", "v", "2", "rs"]
+    );
+    assert_eq!(
+        tokenize("utf8Decode"),
+        vec!["utf8decode", "utf", "8", "decode"]
+    );
+}
+
+#[test]
+fn test_bm25_ranks_matching_documents() {
+    let index = Bm25Index::new(vec![
+        ("src/auth.rs", "fn refresh_token() { lock(); }"),
+        ("src/db.rs", "fn connect() {}"),
+        ("README.md", "Tokens are refreshed on expiry. token token"),
+    ]);
+    let scores = index.score("token refresh");
+    assert!(scores[0] > scores[2], "{:?}", scores);
+    assert!(scores[2] > 0.0);
+    assert_eq!(scores[1], 0.0);
+}
+
+#[test]
+fn test_bm25_rare_terms_weigh_more() {
+    let index = Bm25Index::new(vec![
+        ("a.txt", "common rare"),
+        ("b.txt", "common common"),
+        ("c.txt", "common"),
+    ]);
+    let scores = index.score("rare common");
+    assert!(scores[0] > scores[1]);
+    assert!(scores[0] > scores[2]);
+}
+
+#[test]
+fn test_query_orders_and_budgets_by_relevance() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("session.rs"),
+        "// handles tokenRefresh and the race condition on refresh\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("db.rs"), "fn connect_pool() {}\n").unwrap();
+    fs::write(dir.path().join("util.rs"), "fn pad_left() {}\n").unwrap();
+
+    let mut config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        query: Some("token refresh race condition".to_string()),
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert_eq!(files.last().unwrap().rel_path, "session.rs");
+    assert!(output.trim_end().ends_with("race condition on refresh"));
+
+    // With room for a single file, the most relevant one is kept
+    config.max_size = "60".to_string();
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> session.rs"));
+    assert!(!output.contains(">>>> db.rs"));
+    assert!(!output.contains(">>>> util.rs"));
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::{config_for, file, project};
+use yek::{
+    config::YekConfig, repo_map::generate_repo_map, serialize_repo, symbols::top_level_symbols,
+};
+
+#[test]
+fn test_top_level_symbols() {
+    let rust = "use std::fmt;\n\npub struct Config {\n    debug: bool,\n}\n\nimpl Config {\n    pub fn new() -> Self {\n        todo!()\n    }\n}\n\npub(crate) async fn run(config: &Config) {\n}\nconst LIMIT: usize = 3;\nmod tests;\n";
+    let symbols = top_level_symbols("lib.rs", rust);
+    let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["Config", "run", "LIMIT"]);
+    assert_eq!(symbols[1].line, 13);
+    assert_eq!(
+        symbols[1].signature,
+        "pub(crate) async fn run(config: &Config)"
+    );
+
+    let ts = "export class Store {\n  add() {}\n}\nexport const handler = () => 1;\ninterface Props {}\n";
+    let names: Vec<String> = top_level_symbols("a.ts", ts)
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, vec!["Store", "handler", "Props"]);
+
+    let py = "import os\n\nclass Repo:\n    def size(self):\n        pass\n\nasync def main():\n    pass\n";
+    let names: Vec<String> = top_level_symbols("a.py", py)
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, vec!["Repo", "main"]);
+
+    let go = "package main\n\ntype Server struct{}\n\nfunc (s *Server) Start() error {\n\treturn nil\n}\n\nfunc main() {}\n";
+    let names: Vec<String> = top_level_symbols("m.go", go)
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, vec!["Server", "Start", "main"]);
+
+    assert!(top_level_symbols("notes.txt", "fn main() {}").is_empty());
+}
+
+#[test]
+fn test_repo_map_ranks_by_references() {
+    let model = file(
+        "model.rs",
+        "pub struct Rare;\npub struct Common;\npub fn unused() {}\n",
+    );
+    let a = file("a.rs", "use crate::model::Common;\nfn a(c: Common) {}\n");
+    let b = file("b.rs", "fn b(c: Common, r: Rare) {}\n");
+    let map = generate_repo_map(&[&a, &b, &model], &[], 10_000, str::len);
+
+    assert!(map.starts_with("Repository map:\nmodel.rs\n  pub struct Common;\n  pub struct Rare;\n  pub fn unused() {}\n"));
+    // The symbol `a` is declared in a.rs but not referenced elsewhere
+    assert!(map.find("a.rs\n").unwrap() < map.find("b.rs\n").unwrap());
+    assert!(map.ends_with("\n\n"));
+}
+
+#[test]
+fn test_repo_map_respects_budget() {
+    let model = file(
+        "model.rs",
+        "pub struct Rare;\npub struct Common;\npub fn unused() {}\n",
+    );
+    let user = file("user.rs", "Common Common Rare\n");
+    let budget = "Repository map:\nmodel.rs\n  pub struct Common;\n\n".len();
+    let map = generate_repo_map(&[&model, &user], &[], budget, str::len);
+    assert_eq!(map, "Repository map:\nmodel.rs\n  pub struct Common;\n\n");
+
+    assert_eq!(generate_repo_map(&[&model], &[], 5, str::len), "");
+}
+
+#[test]
+fn test_repo_map_header() {
+    let main = format!("fn main() {{ helper(); }}\n// {}\n", "x".repeat(200));
+    let dir = project(&[("lib.rs", "pub fn helper() {}\n"), ("main.rs", &main)]);
+
+    let mut config = YekConfig {
+        repo_map: true,
+        ..config_for(dir.path())
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.starts_with("Repository map:\nlib.rs\n  pub fn helper() {}\nmain.rs\n  fn main() { helper(); }\n\n>>>> "));
+
+    // Files left out by the budget are only listed with --repo-map-excluded
+    config.max_size = "300B".to_string();
+    config.repo_map_share = 30;
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> lib.rs"));
+    assert!(!output.contains(">>>> main.rs"));
+    assert!(!output.contains("main.rs\n"));
+
+    config.repo_map_excluded = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains("main.rs (not included)\n"));
+}
+
+#[test]
+fn test_validate_repo_map() {
+    let mut config = YekConfig {
+        repo_map: true,
+        json: true,
+        ..Default::default()
+    };
+    assert!(config.validate().is_err());
+    config.json = false;
+    config.repo_map_share = 0;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("repo_map_share"));
+}
@@ -0,0 +1,93 @@
+use regex::Regex;
+use std::fs;
+use tempfile::tempdir;
+use yek::{
+    config::YekConfig,
+    models::{LineWindow, ProcessedFile},
+    serialize_repo,
+    snippets::{format_windows, grep_windows, reduce_to_windows},
+};
+
+fn numbered_lines(count: usize) -> String {
+    (1..=count).map(|i| format!("line {}\n", i)).collect()
+}
+
+#[test]
+fn test_grep_windows_merges_overlaps() {
+    let content = numbered_lines(20);
+    let pattern = Regex::new(r"^line (3|6|15)$").unwrap();
+
+    let windows = grep_windows(&content, &pattern, 2);
+    assert_eq!(
+        windows,
+        vec![
+            LineWindow { start: 1, end: 8 },
+            LineWindow { start: 13, end: 17 }
+        ]
+    );
+}
+
+#[test]
+fn test_grep_windows_clamps_to_file_bounds() {
+    let content = numbered_lines(4);
+    let pattern = Regex::new(r"line (1|4)$").unwrap();
+    assert_eq!(
+        grep_windows(&content, &pattern, 5),
+        vec![LineWindow { start: 1, end: 4 }]
+    );
+    assert!(grep_windows(&content, &Regex::new("missing").unwrap(), 1).is_empty());
+}
+
+#[test]
+fn test_reduce_twice_keeps_original_line_numbers() {
+    let mut file = ProcessedFile::new("a.txt".to_string(), numbered_lines(30), 0, 0);
+    reduce_to_windows(&mut file, &[LineWindow { start: 10, end: 20 }]);
+    assert_eq!(file.content.lines().next(), Some("line 10"));
+
+    // Lines 2-3 and 10-11 of the reduced content are original lines 11-12 and 19-20
+    reduce_to_windows(
+        &mut file,
+        &[
+            LineWindow { start: 2, end: 3 },
+            LineWindow { start: 10, end: 11 },
+        ],
+    );
+    assert_eq!(
+        file.windows,
+        vec![
+            LineWindow { start: 11, end: 12 },
+            LineWindow { start: 19, end: 20 }
+        ]
+    );
+    assert_eq!(file.size_bytes, file.content.len());
+    assert_eq!(
+        format_windows(&file.content, &file.windows),
+        " 11 | line 11\n 12 | line 12\n...\n 19 | line 19\n 20 | line 20"
+    );
+}
+
+#[test]
+fn test_grep_mode_reduces_and_drops_files() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("auth.rs"),
+        "use std::sync::Mutex;\n\nfn a() {}\nfn refresh_token() {\n    lock();\n}\nfn b() {}\nfn c() {}\nfn d() {}\nfn e() {}\nfn refresh_token_later() {}\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("db.rs"), "fn connect() {}\n").unwrap();
+
+    let config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        grep: Some("refresh_token".to_string()),
+        context: 1,
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert!(!output.contains("db.rs"));
+    assert!(output.contains(
+        ">>>> auth.rs\n  3 | fn a() {}\n  4 | fn refresh_token() {\n  5 |     lock();\n...\n 10 | fn e() {}\n 11 | fn refresh_token_later() {}"
+    ));
+    assert!(!output.contains("Mutex"));
+}
@@ -0,0 +1,83 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{config::YekConfig, models::ProcessedFile, serialize_repo, split::split_large_files};
+
+fn rust_source() -> String {
+    (1..=3)
+        .map(|i| {
+            format!(
+                "/// Item {i}\npub fn item_{i}() {{\n{}}}\n\n",
+                "    let value = 1;\n".repeat(5)
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_split_at_item_boundaries() {
+    let file = ProcessedFile::new("lib.rs".to_string(), rust_source(), 7, 0);
+    let parts = split_large_files(vec![file], 300, false);
+
+    assert_eq!(parts.len(), 2);
+    assert!(parts[0].content.starts_with("/// Item 1\n"));
+    assert!(parts[0].content.contains("pub fn item_2()"));
+    assert!(parts[1].content.starts_with("/// Item 3\n"));
+    assert_eq!(
+        (parts[1].windows[0].start, parts[1].windows[0].end),
+        (19, 27)
+    );
+    for (index, part) in parts.iter().enumerate() {
+        assert_eq!(part.part.unwrap().index, index + 1);
+        assert_eq!(part.part.unwrap().count, 2);
+        assert_eq!(part.priority, 7);
+        assert!(part.content.len() <= 300);
+    }
+}
+
+#[test]
+fn test_split_falls_back_to_blank_lines() {
+    let paragraph = "word ".repeat(10) + "\n";
+    let content = format!("{p}{p}\n{p}{p}\n{p}", p = paragraph);
+    let file = ProcessedFile::new("notes.txt".to_string(), content, 0, 0);
+    let parts = split_large_files(vec![file], 120, false);
+
+    let starts: Vec<usize> = parts.iter().map(|p| p.windows[0].start).collect();
+    assert_eq!(starts, vec![1, 4, 7]);
+
+    // Small files are left whole
+    let small = ProcessedFile::new("a.txt".to_string(), "short\n".to_string(), 0, 0);
+    let parts = split_large_files(vec![small], 120, false);
+    assert!(parts[0].part.is_none() && parts[0].windows.is_empty());
+}
+
+#[test]
+fn test_split_size_output() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), rust_source()).unwrap();
+
+    let mut config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        split_size: Some("300B".to_string()),
+        ..Default::default()
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> lib.rs (part 1/2, lines 1-18)\n/// Item 1\n"));
+    assert!(output.contains(">>>> lib.rs (part 2/2, lines 19-27)\n/// Item 3\n"));
+    assert!(output.find("part 1/2").unwrap() < output.find("part 2/2").unwrap());
+
+    // A tight budget keeps the parts that fit instead of dropping the file
+    config.max_size = "150B".to_string();
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert!(output.contains("(part 2/2, lines 19-27)"));
+    assert!(!files.is_empty());
+
+    config.max_size = "10MB".to_string();
+    config.json = true;
+    let (output, _) = serialize_repo(&config).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value[1]["part"], serde_json::json!([2, 2]));
+    assert_eq!(value[1]["lines"], serde_json::json!([19, 27]));
+
+    config.split_size = Some("0".to_string());
+    assert!(config.validate().is_err());
+}
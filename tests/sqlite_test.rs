@@ -0,0 +1,73 @@
+#![cfg(feature = "sqlite")]
+
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+use yek::{config::YekConfig, serialize_repo, sqlite::describe_database};
+
+fn create_database(path: &Path) {
+    let connection = Connection::open(path).unwrap();
+    connection
+        .execute_batch(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, avatar BLOB);
+             CREATE INDEX users_name ON users (name);
+             CREATE VIEW named AS SELECT name FROM users WHERE name IS NOT NULL;
+             CREATE TABLE \"audit log\" (entry TEXT);
+             INSERT INTO users VALUES (1, 'Alice', NULL), (2, 'O''Brien', x'0102'), (3, 'Carol', NULL);",
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_describe_database() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.db");
+    create_database(&path);
+
+    assert_eq!(
+        describe_database(&path, 2).unwrap(),
+        "-- SQLite database: 2 tables, 1 indexes, 1 views, 0 triggers\n\n\
+         CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, avatar BLOB);\n\
+         -- 3 rows, first 2:\n\
+         --   (1, 'Alice', NULL)\n\
+         --   (2, 'O''Brien', <blob, 2 bytes>)\n\n\
+         CREATE TABLE \"audit log\" (entry TEXT);\n\
+         -- 0 rows\n\n\
+         CREATE INDEX users_name ON users (name);\n\n\
+         CREATE VIEW named AS SELECT name FROM users WHERE name IS NOT NULL;\n"
+    );
+
+    // Without sample rows only the counts are kept
+    let description = describe_database(&path, 0).unwrap();
+    assert!(description.contains("-- 3 rows\n"));
+    assert!(!description.contains("Alice"));
+}
+
+#[test]
+fn test_sqlite_schema_option() {
+    let dir = tempdir().unwrap();
+    create_database(&dir.path().join("app.db"));
+    fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+    // A file with a database extension that isn't a database stays skipped
+    fs::write(dir.path().join("cache.db"), [0u8, 1, 2, 3]).unwrap();
+
+    let mut config = YekConfig {
+        input_paths: vec![dir.path().to_string_lossy().to_string()],
+        ..Default::default()
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(!output.contains("app.db"));
+
+    config.sqlite_schema = true;
+    config.sqlite_rows = 1;
+    let (output, files) = serialize_repo(&config).unwrap();
+    assert!(output.contains(">>>> app.db\n-- SQLite database: 2 tables"));
+    assert!(output.contains("--   (1, 'Alice', NULL)\n"));
+    assert!(!output.contains("cache.db"));
+    assert_eq!(files.len(), 2);
+
+    // The database is only read: no journal or WAL file is left behind
+    let entries = fs::read_dir(dir.path()).unwrap().count();
+    assert_eq!(entries, 3);
+}
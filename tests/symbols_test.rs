@@ -0,0 +1,188 @@
+use std::fs;
+use tempfile::tempdir;
+use yek::{config::YekConfig, models::LineWindow, serialize_repo, symbols::find_symbol};
+
+const RUST: &str = r#"use std::fmt;
+
+/// Concatenate files
+#[inline]
+pub fn concat_files(files: &[String]) -> String {
+    let s = "}";
+    files.join(s)
+}
+
+pub struct YekConfig {
+    pub debug: bool,
+}
+
+impl YekConfig {
+    /// Validate the config
+    pub fn validate(&self) -> Result<(), String> {
+        if self.debug {
+            return Ok(());
+        }
+        Ok(())
+    }
+}
+
+pub const LIMIT: usize = 10;
+"#;
+
+fn window(start: usize, end: usize) -> Option<LineWindow> {
+    Some(LineWindow { start, end })
+}
+
+#[test]
+fn test_find_rust_symbols() {
+    assert_eq!(find_symbol("lib.rs", RUST, "concat_files"), window(3, 8));
+    assert_eq!(find_symbol("lib.rs", RUST, "YekConfig"), window(10, 12));
+    assert_eq!(
+        find_symbol("lib.rs", RUST, "YekConfig::validate"),
+        window(15, 21)
+    );
+    assert_eq!(find_symbol("lib.rs", RUST, "LIMIT"), window(24, 24));
+    assert_eq!(find_symbol("lib.rs", RUST, "missing"), None);
+    assert_eq!(find_symbol("lib.rs", RUST, "YekConfig::missing"), None);
+}
+
+#[test]
+fn test_find_rust_symbols_after_escaped_char_literals() {
+    let source =
+        "fn backslash() -> char {\n    '\\\\'\n}\n\nfn quote() -> char {\n    '\\''\n}\n\n\
+                  fn byte() -> u8 {\n    b'\\\\'\n}\n\nstruct After;\n";
+    assert_eq!(find_symbol("lib.rs", source, "backslash"), window(1, 3));
+    assert_eq!(find_symbol("lib.rs", source, "quote"), window(5, 7));
+    assert_eq!(find_symbol("lib.rs", source, "byte"), window(9, 11));
+    assert_eq!(find_symbol("lib.rs", source, "After"), window(13, 13));
+}
+
+#[test]
+fn test_find_typescript_symbols() {
+    let source = r#"import { x } from "./x";
+
+/** A store */
+export class Store {
+  private items: string[] = [];
+
+  @log()
+  async add(item: string): Promise<void> {
+    this.items.push(item);
+  }
+}
+
+export const handler = async (req: Request) => {
+  return req;
+};
+
+export type Id = string
+export function helper() { return 1; }
+"#;
+    assert_eq!(find_symbol("s.ts", source, "Store"), window(3, 11));
+    assert_eq!(find_symbol("s.ts", source, "Store::add"), window(7, 10));
+    assert_eq!(find_symbol("s.ts", source, "Store.items"), window(5, 5));
+    assert_eq!(find_symbol("s.ts", source, "handler"), window(13, 15));
+    assert_eq!(find_symbol("s.ts", source, "Id"), window(17, 17));
+    assert_eq!(find_symbol("s.ts", source, "helper"), window(18, 18));
+}
+
+#[test]
+fn test_find_python_symbols() {
+    let source = r#"import os
+
+class Repo:
+    """A repository."""
+
+    @property
+    def size(self) -> int:
+        return len(
+            self.files
+        )
+
+    def other(self):
+        pass
+
+
+def main():
+    run()
+"#;
+    assert_eq!(find_symbol("r.py", source, "Repo"), window(3, 13));
+    assert_eq!(find_symbol("r.py", source, "Repo.size"), window(6, 10));
+    assert_eq!(find_symbol("r.py", source, "Repo::other"), window(12, 13));
+    assert_eq!(find_symbol("r.py", source, "main"), window(16, 17));
+}
+
+#[test]
+fn test_find_go_symbols() {
+    let source = "package main\n\n// Server serves\ntype Server struct {\n\tAddr string\n}\n\n// Start starts\nfunc (s *Server) Start() error {\n\treturn nil\n}\n\ntype ID int\n\nfunc main() {\n\tprintln(\"}\")\n}\n";
+    assert_eq!(find_symbol("m.go", source, "Server"), window(3, 6));
+    assert_eq!(find_symbol("m.go", source, "Server.Start"), window(8, 11));
+    assert_eq!(find_symbol("m.go", source, "ID"), window(13, 13));
+    assert_eq!(find_symbol("m.go", source, "main"), window(15, 17));
+}
+
+#[test]
+fn test_symbol_input_paths() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("lib.rs");
+    fs::write(&file, RUST).unwrap();
+    let file = file.to_string_lossy().to_string();
+
+    let config = YekConfig {
+        input_paths: vec![
+            format!("{}#concat_files", file),
+            format!("{}#YekConfig::validate", file),
+            format!("{}#missing", file),
+        ],
+        ..Default::default()
+    };
+    let (output, files) = serialize_repo(&config).unwrap();
+
+    assert_eq!(files.len(), 2);
+    assert!(output.contains(
+        ">>>> lib.rs#concat_files (lines 3-8)\n/// Concatenate files\n#[inline]\npub fn concat_files"
+    ));
+    assert!(output
+        .contains(">>>> lib.rs#YekConfig::validate (lines 15-21)\n    /// Validate the config\n"));
+    assert!(!output.contains("pub struct YekConfig"));
+    assert!(!output.contains("LIMIT"));
+}
+
+#[test]
+fn test_symbol_with_line_numbers_keeps_original_numbers() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("lib.rs");
+    fs::write(&file, RUST).unwrap();
+
+    let config = YekConfig {
+        input_paths: vec![format!("{}#LIMIT", file.to_string_lossy())],
+        line_numbers: true,
+        ..Default::default()
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    assert!(output.contains(
+        ">>>> lib.rs#LIMIT (lines 24-24)
+ 24 | pub const LIMIT"
+    ));
+}
+
+#[test]
+fn test_symbol_input_paths_json() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("lib.rs");
+    fs::write(&file, RUST).unwrap();
+
+    let config = YekConfig {
+        input_paths: vec![format!("{}#YekConfig", file.to_string_lossy())],
+        json: true,
+        ..Default::default()
+    };
+    let (output, _) = serialize_repo(&config).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value[0]["filename"], "lib.rs");
+    assert_eq!(value[0]["selection"], "#YekConfig");
+    assert_eq!(value[0]["lines"], serde_json::json!([10, 12]));
+    assert!(value[0]["content"]
+        .as_str()
+        .unwrap()
+        .starts_with("pub struct YekConfig {"));
+}